@@ -0,0 +1,63 @@
+use std::collections::{HashMap, HashSet};
+use crate::tokenizer::find_operators;
+
+/// The operators after which a new command starts, longest first.
+const COMMAND_SEPARATORS: &[&str] = &["&&", "||", ";", "\n", "&", "|"];
+
+/// Replaces the name at the start of each command in `line` with its alias.
+/// That's done before the line is split up, since the value is read as part
+/// of the line, so `alias up='cd .. && ls'` runs both commands. The commands
+/// in a value are expanded in turn, but an alias is used at most once inside
+/// its own expansion, so `alias ls='ls -F'` expands to `ls -F` rather than
+/// looping.
+pub fn expand_aliases(line: &str, aliases: &HashMap<String, String>) -> String {
+    expand_commands(line, aliases, &HashSet::new())
+}
+
+fn expand_commands(line: &str, aliases: &HashMap<String, String>, used: &HashSet<&str>) -> String {
+    let mut expanded = String::new();
+    let mut start = 0;
+    for (pos, op) in find_operators(line, COMMAND_SEPARATORS).into_iter().chain([(line.len(), "")]) {
+        expanded.push_str(&expand_command(&line[start..pos], aliases, used));
+        expanded.push_str(op);
+        start = pos + op.len();
+    }
+    expanded
+}
+
+/// Expands the alias at the start of a single command, if there is one.
+fn expand_command(command: &str, aliases: &HashMap<String, String>, used: &HashSet<&str>) -> String {
+    let trimmed = command.trim_start();
+    let end = trimmed
+        .find(|c: char| c.is_whitespace() || ";|&<>()".contains(c))
+        .unwrap_or(trimmed.len());
+    let name = &trimmed[..end];
+    let Some((name, value)) = aliases.get_key_value(name).filter(|(name, _)| !used.contains(name.as_str())) else {
+        return command.to_string();
+    };
+    let mut used = used.clone();
+    used.insert(name);
+    let leading = &command[..command.len() - trimmed.len()];
+    format!("{}{}", leading, expand_commands(&format!("{}{}", value, &trimmed[end..]), aliases, &used))
+}
+
+/// Whether `name` can be defined as an alias. Quoting or escaping a command
+/// name is how an alias gets bypassed, so names can't contain those.
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || "=/'\"\\$`;|&<>()".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alias_names() {
+        for name in ["ll", "g++", "..", "1", "-x"] {
+            assert!(valid_name(name), "{:?} should be an alias name", name);
+        }
+        for name in ["", "a/b", "a=b", "'q'", "a b", "$x", "a;b", "a|b", "a&b", "<", "(x)"] {
+            assert!(!valid_name(name), "{:?} shouldn't be an alias name", name);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::env;
+use crate::error::ShellError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Op(char),
+}
+
+/// Evaluates an integer arithmetic expression with `+ - * / %`, unary signs
+/// and parentheses. A bare name stands for the value of that variable, with
+/// unset or empty variables counting as 0.
+pub fn evaluate(expression: &str) -> Result<i64, ShellError> {
+    let tokens = lex(expression)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0, expression };
+    let value = parser.sum()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(value),
+        Some(_) => Err(parser.syntax_error()),
+    }
+}
+
+fn lex(expression: &str) -> Result<Vec<Token>, ShellError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let word = &expression[start..end];
+            if c.is_ascii_digit() {
+                let number = word
+                    .parse()
+                    .map_err(|_| ShellError::Arithmetic(format!("{}: invalid number", word)))?;
+                tokens.push(Token::Number(number));
+            } else {
+                tokens.push(Token::Name(word.to_string()));
+            }
+        } else if "+-*/%()".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            return Err(syntax_error(expression));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    expression: &'a str,
+}
+
+impl Parser<'_> {
+    fn next_op(&mut self, ops: &str) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(*op) => {
+                self.pos += 1;
+                Some(*op)
+            }
+            _ => None,
+        }
+    }
+
+    /// sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<i64, ShellError> {
+        let mut value = self.product()?;
+        while let Some(op) = self.next_op("+-") {
+            let rhs = self.product()?;
+            value = if op == '+' { value.wrapping_add(rhs) } else { value.wrapping_sub(rhs) };
+        }
+        Ok(value)
+    }
+
+    /// product := unary (('*' | '/' | '%') unary)*
+    fn product(&mut self) -> Result<i64, ShellError> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.next_op("*/%") {
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value.wrapping_mul(rhs),
+                _ if rhs == 0 => {
+                    return Err(ShellError::Arithmetic(format!("{}: division by 0", self.expression.trim())));
+                }
+                '/' => value.wrapping_div(rhs),
+                _ => value.wrapping_rem(rhs),
+            };
+        }
+        Ok(value)
+    }
+
+    /// unary := ('+' | '-') unary | primary
+    fn unary(&mut self) -> Result<i64, ShellError> {
+        match self.next_op("+-") {
+            Some('-') => Ok(self.unary()?.wrapping_neg()),
+            Some(_) => self.unary(),
+            None => self.primary(),
+        }
+    }
+
+    /// primary := number | name | '(' sum ')'
+    fn primary(&mut self) -> Result<i64, ShellError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Name(name)) => variable(&name),
+            Some(Token::Op('(')) => {
+                let value = self.sum()?;
+                match self.next_op(")") {
+                    Some(_) => Ok(value),
+                    None => Err(self.syntax_error()),
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.syntax_error())
+            }
+        }
+    }
+
+    fn syntax_error(&self) -> ShellError {
+        syntax_error(self.expression)
+    }
+}
+
+fn syntax_error(expression: &str) -> ShellError {
+    ShellError::Arithmetic(format!("{}: syntax error in expression", expression.trim()))
+}
+
+/// The value of a variable used in an expression, which itself has to be a
+/// plain number.
+fn variable(name: &str) -> Result<i64, ShellError> {
+    let value = env::var(name).unwrap_or_default();
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(0);
+    }
+    value.parse().map_err(|_| ShellError::Arithmetic(format!("{}: {}: not a number", name, value)))
+}
@@ -0,0 +1,181 @@
+use crate::expand::parse_assignment;
+use crate::tokenizer::{Quote, Segment, Word};
+
+/// A word taken apart for brace expansion: unquoted characters, which may
+/// be braces and commas, and the segments nothing in them can be.
+#[derive(Clone)]
+enum Piece {
+    Char(char),
+    Opaque(Segment),
+}
+
+/// The most words one word's braces can expand to, counting every
+/// combination of nested and adjacent braces. A word that would make more
+/// is left as it is rather than filling memory.
+const MAX_WORDS: usize = 100_000;
+
+/// Expands `{a,b}` lists and `{1..5}` or `{a..e}` ranges in `word`, giving
+/// one word for each alternative, in order. Braces with neither in them,
+/// quoted braces and `${...}` are left as they are, as is a `NAME=value`
+/// word or one that would expand to more than `MAX_WORDS`.
+pub fn expand_braces(word: &Word) -> Vec<Word> {
+    let assignment = word.segments.first().is_some_and(|segment| {
+        segment.quote == Quote::None && segment.substitution.is_none() && parse_assignment(&segment.text).is_some()
+    });
+    if assignment || !word.segments.iter().any(|segment| is_plain(segment) && segment.text.contains('{')) {
+        return vec![word.clone()];
+    }
+
+    let mut pieces = Vec::new();
+    for segment in &word.segments {
+        if is_plain(segment) {
+            pieces.extend(segment.text.chars().map(Piece::Char));
+        } else {
+            pieces.push(Piece::Opaque(segment.clone()));
+        }
+    }
+    match expand(&pieces) {
+        Some(words) => words.iter().map(|pieces| rebuild(pieces)).collect(),
+        None => vec![word.clone()],
+    }
+}
+
+fn is_plain(segment: &Segment) -> bool {
+    segment.quote == Quote::None && segment.substitution.is_none()
+}
+
+/// The expansions of `pieces`, or `None` if there would be more than
+/// `MAX_WORDS` of them.
+fn expand(pieces: &[Piece]) -> Option<Vec<Vec<Piece>>> {
+    let mut start = 0;
+    while let Some(open) = find_open(pieces, start) {
+        let Some(close) = find_close(pieces, open) else {
+            break;
+        };
+        let body = &pieces[open + 1..close];
+        let alternatives = split_commas(body).or_else(|| sequence(body));
+        let Some(alternatives) = alternatives else {
+            start = open + 1;
+            continue;
+        };
+
+        // Each alternative gets the text around the braces, which may have
+        // braces of its own to expand.
+        let (prefix, suffix) = (&pieces[..open], &pieces[close + 1..]);
+        let mut words = Vec::new();
+        for alternative in alternatives {
+            let joined: Vec<Piece> = prefix.iter().chain(&alternative).chain(suffix).cloned().collect();
+            words.extend(expand(&joined)?);
+            if words.len() > MAX_WORDS {
+                return None;
+            }
+        }
+        return Some(words);
+    }
+    Some(vec![pieces.to_vec()])
+}
+
+/// The first unquoted `{` at or after `start` that isn't part of `${`.
+fn find_open(pieces: &[Piece], start: usize) -> Option<usize> {
+    (start..pieces.len()).find(|&i| is_char(&pieces[i], '{') && (i == 0 || !is_char(&pieces[i - 1], '$')))
+}
+
+/// The `}` that closes the `{` at `open`, skipping nested pairs.
+fn find_close(pieces: &[Piece], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, piece) in pieces.iter().enumerate().skip(open + 1) {
+        if is_char(piece, '{') {
+            depth += 1;
+        } else if is_char(piece, '}') {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Splits the inside of a brace on its top-level commas, if it has any.
+fn split_commas(body: &[Piece]) -> Option<Vec<Vec<Piece>>> {
+    let mut alternatives = vec![Vec::new()];
+    let mut depth = 0;
+    for piece in body {
+        if is_char(piece, '{') {
+            depth += 1;
+        } else if is_char(piece, '}') {
+            depth -= 1;
+        } else if depth == 0 && is_char(piece, ',') {
+            alternatives.push(Vec::new());
+            continue;
+        }
+        alternatives.last_mut()?.push(piece.clone());
+    }
+    (alternatives.len() > 1).then_some(alternatives)
+}
+
+/// Expands `x..y` or `x..y..step`, where `x` and `y` are both integers or
+/// both single letters. Integers written with a leading zero are padded to
+/// the same width.
+fn sequence(body: &[Piece]) -> Option<Vec<Vec<Piece>>> {
+    let text: String = body
+        .iter()
+        .map(|piece| match piece {
+            Piece::Char(c) => Some(*c),
+            Piece::Opaque(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    let parts: Vec<&str> = text.split("..").collect();
+    let (first, last, step) = match parts.as_slice() {
+        [first, last] => (*first, *last, 1),
+        [first, last, step] => (*first, *last, step.parse::<i64>().ok()?.unsigned_abs().max(1)),
+        _ => return None,
+    };
+
+    let values: Vec<String> = if let (Ok(from), Ok(to)) = (first.parse::<i64>(), last.parse::<i64>()) {
+        let padded = [first, last].iter().any(|n| n.trim_start_matches('-').starts_with('0') && n.len() > 1);
+        let width = if padded { first.len().max(last.len()) } else { 0 };
+        range(from, to, step)?.map(|n| format!("{:0width$}", n, width = width)).collect()
+    } else {
+        let (from, to) = (single_letter(first)?, single_letter(last)?);
+        range(from as i64, to as i64, step)?.filter_map(|n| char::from_u32(n as u32)).map(String::from).collect()
+    };
+    Some(values.into_iter().map(|value| value.chars().map(Piece::Char).collect()).collect())
+}
+
+/// The numbers from `from` towards `to`, `step` apart, or `None` if there
+/// are more than `MAX_WORDS` of them.
+fn range(from: i64, to: i64, step: u64) -> Option<impl Iterator<Item = i64>> {
+    let count = (from.abs_diff(to) / step).checked_add(1).filter(|&count| count <= MAX_WORDS as u64)?;
+    let step = if from <= to { i128::from(step) } else { -i128::from(step) };
+    // Every value lies between `from` and `to`, but a step can be too big
+    // for an `i64` by itself.
+    Some((0..count).map(move |i| (i128::from(from) + i128::from(i) * step) as i64))
+}
+
+fn single_letter(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c),
+        _ => None,
+    }
+}
+
+fn is_char(piece: &Piece, c: char) -> bool {
+    matches!(piece, Piece::Char(p) if *p == c)
+}
+
+/// Puts expanded pieces back together into a word.
+fn rebuild(pieces: &[Piece]) -> Word {
+    let mut word = Word::default();
+    for piece in pieces {
+        match piece {
+            Piece::Char(c) => match word.segments.last_mut() {
+                Some(segment) if is_plain(segment) => segment.text.push(*c),
+                _ => word.segments.push(Segment { text: c.to_string(), quote: Quote::None, substitution: None }),
+            },
+            Piece::Opaque(segment) => word.segments.push(segment.clone()),
+        }
+    }
+    word
+}
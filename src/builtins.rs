@@ -0,0 +1,1081 @@
+use nix::sys::signal::{self, Signal};
+use nix::sys::stat::{self, Mode};
+use nix::unistd::{chdir, getcwd, setpgid, ForkResult, Pid};
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::mem::ManuallyDrop;
+use std::os::fd::FromRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::alias::valid_name;
+use crate::color;
+use crate::condition;
+use crate::error::{describe, ShellError};
+use crate::executor::run_execvp;
+use crate::expand::is_name;
+use crate::input::{LineSource, Terminal};
+use crate::jobs::{exit_code, fork_failed, fork_retrying, wait_for, wait_until};
+use crate::path::{abbreviate_home, find_in_path, logical_cwd, normalize};
+use crate::shell::{Shell, ShellOptions};
+use crate::signals::{self, parse_signal, Disposition};
+
+pub struct Builtin {
+    pub name: &'static str,
+    /// A synopsis of the arguments, for `help`.
+    pub usage: &'static str,
+    /// A one-line description, for `help`.
+    pub summary: &'static str,
+    pub run: fn(&mut Shell, &[String]) -> i32,
+}
+
+/// Prints a line of a builtin's output like `println!`, but a write that
+/// fails, as to a full disk, is reported and makes the builtin return 1
+/// instead of panicking. The first argument names the builtin.
+macro_rules! out {
+    ($name:expr, $($arg:tt)*) => {
+        if let Err(err) = write_stdout(&format!("{}\n", format_args!($($arg)*))) {
+            eprintln!("{}: write error: {}", $name, describe(&err));
+            return 1;
+        }
+    };
+}
+
+pub const BUILTINS: &[Builtin] = &[
+    Builtin { name: ".", usage: ". file [arg ...]", summary: "Run the commands in a file in this shell.", run: source },
+    Builtin { name: ":", usage: ": [arg ...]", summary: "Do nothing, successfully.", run: succeed },
+    Builtin { name: "[", usage: "[ expression ]", summary: "Test a condition, like test.", run: test },
+    Builtin { name: "alias", usage: "alias [name[=value] ...]", summary: "Define or list aliases.", run: alias },
+    Builtin { name: "bg", usage: "bg [%job]", summary: "Resume a stopped job in the background.", run: bg },
+    Builtin { name: "cd", usage: "cd [-L|-P] [-|dir]", summary: "Change the working directory.", run: cd },
+    Builtin { name: "dirs", usage: "dirs", summary: "List the directory stack.", run: dirs },
+    Builtin { name: "echo", usage: "echo [-neE] [arg ...]", summary: "Print the arguments.", run: echo },
+    Builtin { name: "exec", usage: "exec [command [arg ...]]", summary: "Replace the shell with a command, or keep its redirections.", run: exec },
+    Builtin { name: "exit", usage: "exit [n]", summary: "Exit the shell with status n, or the last status.", run: exit_shell },
+    Builtin { name: "export", usage: "export [-n] [name[=value] ...]", summary: "Set environment variables, or list them.", run: export },
+    Builtin { name: "false", usage: "false", summary: "Fail with status 1.", run: fail },
+    Builtin { name: "fg", usage: "fg [%job]", summary: "Bring a job to the foreground.", run: fg },
+    Builtin { name: "help", usage: "help [name]", summary: "Describe the builtins.", run: help },
+    Builtin { name: "history", usage: "history", summary: "List the commands entered so far.", run: history },
+    Builtin { name: "jobs", usage: "jobs", summary: "List background and stopped jobs.", run: jobs },
+    Builtin { name: "kill", usage: "kill [-sig] pid|%job ...", summary: "Signal processes or jobs, with SIGTERM by default.", run: send_signal },
+    Builtin { name: "popd", usage: "popd", summary: "Return to the directory on top of the stack.", run: popd },
+    Builtin { name: "pushd", usage: "pushd [dir]", summary: "Change to a directory, saving the current one on the stack.", run: pushd },
+    Builtin { name: "pwd", usage: "pwd [-L|-P]", summary: "Print the working directory.", run: pwd },
+    Builtin { name: "read", usage: "read [-p prompt] name ...", summary: "Read a line of input into variables.", run: read },
+    Builtin { name: "set", usage: "set [-Ceux] [-o option] [+o option]", summary: "List every variable, or turn options on and off.", run: set },
+    Builtin { name: "source", usage: "source file [arg ...]", summary: "Run the commands in a file in this shell.", run: source },
+    Builtin { name: "test", usage: "test [expression]", summary: "Test a condition: files, strings or integers.", run: test },
+    Builtin { name: "timeout", usage: "timeout secs command ...", summary: "Run a command, ending it after secs.", run: timeout },
+    Builtin { name: "trap", usage: "trap [command] signal ...", summary: "Run a command when a signal arrives, or ignore it.", run: trap },
+    Builtin { name: "true", usage: "true", summary: "Do nothing, successfully.", run: succeed },
+    Builtin { name: "type", usage: "type name ...", summary: "Tell how each name would be run.", run: type_of },
+    Builtin { name: "umask", usage: "umask [mode]", summary: "Show or set the file creation mask.", run: umask },
+    Builtin { name: "unalias", usage: "unalias [-a] name ...", summary: "Remove aliases.", run: unalias },
+    Builtin { name: "unset", usage: "unset name ...", summary: "Remove variables.", run: unset },
+    Builtin { name: "wait", usage: "wait [pid|%job ...]", summary: "Wait for jobs to finish, returning the last one's status.", run: wait },
+    Builtin { name: "which", usage: "which name ...", summary: "Print the path of each program on $PATH.", run: which },
+];
+
+pub fn find_builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
+
+/// Runs `args` as a builtin if its first word names one, returning the exit
+/// status, or `None` when it should be run as an external command.
+pub fn try_run_builtin(shell: &mut Shell, args: &[String]) -> Option<i32> {
+    let builtin = find_builtin(args.first()?)?;
+    Some((builtin.run)(shell, args))
+}
+
+/// Replaces the shell with a command. Without one there's nothing to do
+/// here: the shell keeps the redirections instead of undoing them after.
+fn exec(_shell: &mut Shell, args: &[String]) -> i32 {
+    let Some(program) = args.get(1) else {
+        return 0;
+    };
+    // A program that isn't there leaves the shell running, rather than
+    // ending it the way a failed exec would.
+    let found = if program.contains('/') { Path::new(program).exists() } else { find_in_path(program).is_some() };
+    if !found {
+        eprintln!("exec: {}: not found", program);
+        return 127;
+    }
+    let _ = io::stdout().flush();
+    signals::reset_child_signals();
+    run_execvp(&args[1..]);
+}
+
+fn exit_shell(shell: &mut Shell, args: &[String]) -> i32 {
+    let status = match args.get(1) {
+        Some(code) => code.parse().unwrap_or_else(|_| {
+            eprintln!("exit: numeric argument required");
+            2
+        }),
+        None => shell.last_status,
+    };
+    shell.last_status = status;
+    shell.exiting = true;
+    status
+}
+
+/// Writes straight to descriptor 1 rather than through the buffer behind
+/// `io::stdout()`, which would keep what a failed write couldn't deliver
+/// and send it on to whatever stdout is restored afterwards.
+fn write_stdout(text: &str) -> io::Result<()> {
+    io::stdout().flush()?;
+    // Descriptor 1 belongs to the shell, so it mustn't be closed here.
+    let mut stdout = ManuallyDrop::new(unsafe { File::from_raw_fd(1) });
+    stdout.write_all(text.as_bytes())
+}
+
+fn succeed(_shell: &mut Shell, _args: &[String]) -> i32 {
+    0
+}
+
+fn fail(_shell: &mut Shell, _args: &[String]) -> i32 {
+    1
+}
+
+fn history(shell: &mut Shell, _args: &[String]) -> i32 {
+    for (i, entry) in shell.history.entries().iter().enumerate() {
+        out!("history", "{:>5}  {}", i + 1, entry);
+    }
+    0
+}
+
+/// Changes directory. By default, or with `-L`, symlinks stay in `$PWD` and
+/// `..` undoes the last step of it; `-P` resolves them first.
+fn cd(_shell: &mut Shell, args: &[String]) -> i32 {
+    let mut physical = false;
+    let mut args = &args[1..];
+    while let Some(flag) = args.first() {
+        match flag.as_str() {
+            "-L" => physical = false,
+            "-P" => physical = true,
+            "--" => {
+                args = &args[1..];
+                break;
+            }
+            _ => break,
+        }
+        args = &args[1..];
+    }
+
+    // `cd -` prints where it went, once it has got there.
+    let back = args.first().is_some_and(|arg| arg == "-");
+    let target = match args.first().map(String::as_str) {
+        Some("-") => match env::var("OLDPWD") {
+            Ok(old_dir) => old_dir,
+            Err(_) => {
+                eprintln!("cd: OLDPWD not set");
+                return 1;
+            }
+        },
+        Some(target) => target.to_string(),
+        None => env::var("HOME").unwrap_or_else(|_| "/".to_string()),
+    };
+    // A directory found through a `$CDPATH` entry is printed, since it
+    // isn't where the name alone would lead.
+    let (target, found) = search_cdpath(&target).unwrap_or_else(|| (PathBuf::from(target), false));
+    if let Err(err) = change_dir(&target, physical) {
+        eprintln!("{}", color::error(&format!("cd :{}", err)));
+        return 1;
+    }
+    if back {
+        out!("cd", "{}", target.display());
+    } else if found && let Ok(dir) = logical_cwd() {
+        out!("cd", "{}", dir.display());
+    }
+    0
+}
+
+/// Looks for `target` in each `$CDPATH` directory in turn, where an empty
+/// entry is the current directory. Returns the first match and whether it
+/// came from a named entry. Absolute targets and ones starting with `.` or
+/// `..` don't use `$CDPATH`.
+fn search_cdpath(target: &str) -> Option<(PathBuf, bool)> {
+    let first = target.split('/').next().unwrap_or_default();
+    if target.starts_with('/') || first == "." || first == ".." {
+        return None;
+    }
+    let cdpath = env::var("CDPATH").ok()?;
+    cdpath.split(':').find_map(|entry| {
+        let dir = Path::new(if entry.is_empty() { "." } else { entry }).join(target);
+        dir.is_dir().then_some((dir, !entry.is_empty()))
+    })
+}
+
+/// Changes to `target`, keeping `$PWD` and `$OLDPWD` up to date. A relative
+/// `target` is taken from the logical working directory unless `physical`.
+fn change_dir(target: &Path, physical: bool) -> nix::Result<()> {
+    let old_dir = logical_cwd();
+    let logical = old_dir.as_ref().ok().filter(|_| !physical).map(|dir| normalize(&dir.join(target)));
+
+    // A logical path that doesn't work, say because `..` passes through a
+    // directory that's gone, falls back to the physical one.
+    let new_dir = match logical {
+        Some(dir) if chdir(&dir).is_ok() => Ok(dir),
+        _ => {
+            chdir(target)?;
+            getcwd()
+        }
+    };
+    if let Ok(old_dir) = old_dir {
+        unsafe { env::set_var("OLDPWD", old_dir) };
+    }
+    if let Ok(new_dir) = new_dir {
+        unsafe { env::set_var("PWD", new_dir) };
+    }
+    Ok(())
+}
+
+fn pushd(shell: &mut Shell, args: &[String]) -> i32 {
+    let current = match logical_cwd() {
+        Ok(dir) => dir,
+        Err(err) => {
+            eprintln!("pushd: {}", err.desc());
+            return 1;
+        }
+    };
+    // With no directory, swap the current one with the top of the stack.
+    let target = match args.get(1) {
+        Some(dir) => PathBuf::from(dir),
+        None => match shell.dir_stack.pop() {
+            Some(top) => top,
+            None => {
+                eprintln!("pushd: no other directory");
+                return 1;
+            }
+        },
+    };
+    if let Err(err) = change_dir(&target, false) {
+        eprintln!("pushd: {}: {}", target.display(), err.desc());
+        if args.get(1).is_none() {
+            shell.dir_stack.push(target);
+        }
+        return 1;
+    }
+    shell.dir_stack.push(current);
+    dirs(shell, &[])
+}
+
+fn popd(shell: &mut Shell, _args: &[String]) -> i32 {
+    let Some(top) = shell.dir_stack.last() else {
+        eprintln!("popd: directory stack empty");
+        return 1;
+    };
+    if let Err(err) = change_dir(top, false) {
+        eprintln!("popd: {}: {}", top.display(), err.desc());
+        return 1;
+    }
+    shell.dir_stack.pop();
+    dirs(shell, &[])
+}
+
+/// Prints the working directory followed by the stack, most recent first.
+fn dirs(shell: &mut Shell, args: &[String]) -> i32 {
+    if let Some(arg) = args.get(1) {
+        eprintln!("dirs: {}: invalid option", arg);
+        return 2;
+    }
+    let mut entries = vec![logical_cwd().map_or_else(|_| "?".to_string(), |dir| abbreviate_home(&dir))];
+    entries.extend(shell.dir_stack.iter().rev().map(|dir| abbreviate_home(dir)));
+    out!("dirs", "{}", entries.join(" "));
+    0
+}
+
+fn echo(_shell: &mut Shell, args: &[String]) -> i32 {
+    let mut newline = true;
+    let mut escapes = false;
+    let mut words = &args[1..];
+    while let Some(flag) = words.first().and_then(|word| word.strip_prefix('-')) {
+        if flag.is_empty() || !flag.chars().all(|c| matches!(c, 'n' | 'e' | 'E')) {
+            break;
+        }
+        for c in flag.chars() {
+            match c {
+                'n' => newline = false,
+                'e' => escapes = true,
+                _ => escapes = false,
+            }
+        }
+        words = &words[1..];
+    }
+
+    let mut output = words.join(" ");
+    if escapes {
+        let (text, stop) = interpret_escapes(&output);
+        output = text;
+        newline &= !stop;
+    }
+    if newline {
+        output.push('\n');
+    }
+    if let Err(err) = write_stdout(&output) {
+        eprintln!("echo: write error: {}", describe(&err));
+        return 1;
+    }
+    0
+}
+
+/// Prints the current directory as `cd` left it in `$PWD`, or with `-P`
+/// with its symlinks resolved.
+fn pwd(_shell: &mut Shell, args: &[String]) -> i32 {
+    let physical = args.get(1).is_some_and(|arg| arg == "-P");
+    match if physical { getcwd() } else { logical_cwd() } {
+        Ok(dir) => out!("pwd", "{}", dir.display()),
+        Err(err) => {
+            eprintln!("pwd: {}", err);
+            return 1;
+        }
+    }
+    0
+}
+
+fn alias(shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        let mut aliases: Vec<_> = shell.aliases.iter().collect();
+        aliases.sort();
+        for (name, value) in aliases {
+            if print_alias(name, value) != 0 {
+                return 1;
+            }
+        }
+        return 0;
+    }
+
+    let mut status = 0;
+    for arg in &args[1..] {
+        match arg.split_once('=') {
+            Some((name, value)) if valid_name(name) => {
+                shell.aliases.insert(name.to_string(), value.to_string());
+            }
+            Some(_) => {
+                eprintln!("alias: `{}': invalid alias name", arg);
+                status = 1;
+            }
+            None => match shell.aliases.get(arg) {
+                Some(value) => status |= print_alias(arg, value),
+                None => {
+                    eprintln!("alias: {}: not found", arg);
+                    status = 1;
+                }
+            },
+        }
+    }
+    status
+}
+
+/// Prints an alias in a form that can be read back in.
+fn print_alias(name: &str, value: &str) -> i32 {
+    out!("alias", "alias {}='{}'", name, value.replace('\'', "'\\''"));
+    0
+}
+
+/// Says how each name would be run: as an alias, a builtin, or a program
+/// found on `$PATH`, checked in the order the shell resolves them.
+fn type_of(shell: &mut Shell, args: &[String]) -> i32 {
+    let mut status = 0;
+    for name in &args[1..] {
+        if let Some(value) = shell.aliases.get(name) {
+            out!("type", "{} is aliased to `{}'", name, value);
+        } else if find_builtin(name).is_some() {
+            out!("type", "{} is a shell builtin", name);
+        } else if let Some(path) = find_in_path(name) {
+            out!("type", "{} is {}", name, path.display());
+        } else {
+            eprintln!("type: {}: not found", name);
+            status = 1;
+        }
+    }
+    status
+}
+
+/// Prints where each program would be found, saying nothing about the ones
+/// that can't be.
+fn which(_shell: &mut Shell, args: &[String]) -> i32 {
+    let mut status = 0;
+    for name in &args[1..] {
+        match find_in_path(name) {
+            Some(path) => out!("which", "{}", path.display()),
+            None => status = 1,
+        }
+    }
+    status
+}
+
+/// Runs the commands in a file in this shell, so that what they define
+/// stays defined. Any arguments after the file become the positional
+/// parameters while it runs.
+fn source(shell: &mut Shell, args: &[String]) -> i32 {
+    let Some(path) = args.get(1) else {
+        eprintln!("{}: filename argument required", args[0]);
+        return 2;
+    };
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("{}: {}: {}", args[0], path, describe(&err));
+            return 1;
+        }
+    };
+
+    let saved = (args.len() > 2).then(|| {
+        let params = shell.positional.first().into_iter().chain(&args[2..]).cloned().collect();
+        std::mem::replace(&mut shell.positional, params)
+    });
+    shell.run_script(&mut BufReader::new(file), path);
+    if let Some(saved) = saved {
+        shell.positional = saved;
+    }
+    shell.last_status
+}
+
+/// Lists every builtin, or describes the ones named.
+fn help(_shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        let width = BUILTINS.iter().map(|builtin| builtin.usage.len()).max().unwrap_or(0);
+        for builtin in BUILTINS {
+            out!("help", "{:<width$}  {}", builtin.usage, builtin.summary, width = width);
+        }
+        return 0;
+    }
+
+    let mut status = 0;
+    for name in &args[1..] {
+        match find_builtin(name) {
+            Some(builtin) => out!("help", "{}: {}\n    {}", builtin.name, builtin.usage, builtin.summary),
+            None => {
+                eprintln!("help: no help topics match '{}'", name);
+                status = 1;
+            }
+        }
+    }
+    status
+}
+
+fn unalias(shell: &mut Shell, args: &[String]) -> i32 {
+    if args.get(1).is_some_and(|arg| arg == "-a") {
+        shell.aliases.clear();
+        return 0;
+    }
+
+    let mut status = 0;
+    for name in &args[1..] {
+        if shell.aliases.remove(name).is_none() {
+            eprintln!("unalias: {}: not found", name);
+            status = 1;
+        }
+    }
+    status
+}
+
+fn export(_shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        for (name, value) in sorted_vars() {
+            out!("export", "export {}=\"{}\"", name, value);
+        }
+    }
+    // `-n` takes the names out of the environment again.
+    let remove = args.get(1).is_some_and(|arg| arg == "-n");
+    let mut status = 0;
+    // Every variable already lives in the environment, so a bare
+    // `export NAME` has nothing left to do.
+    for arg in &args[if remove { 2 } else { 1 }..] {
+        let (name, value) = arg.split_once('=').map_or((arg.as_str(), None), |(name, value)| (name, Some(value)));
+        if !is_name(name) {
+            eprintln!("export: '{}': not a valid identifier", arg);
+            status = 1;
+        } else if remove {
+            unsafe { env::remove_var(name) };
+        } else if value.is_some_and(|value| value.contains('\0')) {
+            eprintln!("{}", ShellError::NulByte);
+            status = 1;
+        } else if let Some(value) = value {
+            unsafe { env::set_var(name, value) };
+        }
+    }
+    status
+}
+
+/// Reads a line from stdin and splits it on whitespace among the named
+/// variables, the last of which takes whatever is left.
+fn read(_shell: &mut Shell, args: &[String]) -> i32 {
+    let mut names = &args[1..];
+    let mut prompt = "";
+    if names.first().is_some_and(|arg| arg == "-p") {
+        let Some(text) = names.get(1) else {
+            eprintln!("read: -p: option requires an argument");
+            return 2;
+        };
+        prompt = text;
+        names = &names[2..];
+    }
+    if names.is_empty() {
+        eprintln!("read: usage: read [-p prompt] name ...");
+        return 2;
+    }
+    if let Some(name) = names.iter().find(|name| !is_name(name)) {
+        eprintln!("read: `{}': not a valid identifier", name);
+        return 1;
+    }
+
+    let mut line = String::new();
+    match Terminal.read_line(prompt, &mut line) {
+        Ok(0) => return 1,
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("read: {}", describe(&err));
+            return 1;
+        }
+    }
+
+    // A NUL can't be kept in a variable, so like bash, drop any.
+    line.retain(|c| c != '\0');
+    let mut rest = line.trim_end_matches('\n').trim_start();
+    for (i, name) in names.iter().enumerate() {
+        let value = if i + 1 == names.len() {
+            std::mem::take(&mut rest).trim_end()
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (field, after) = rest.split_at(end);
+            rest = after.trim_start();
+            field
+        };
+        unsafe { env::set_var(name, value) };
+    }
+    0
+}
+
+/// Evaluates a conditional expression, returning 0 when it's true, 1 when
+/// it's false and 2 when it can't be read. `[` wants a `]` after it.
+fn test(_shell: &mut Shell, args: &[String]) -> i32 {
+    let mut operands = &args[1..];
+    if args[0] == "[" {
+        match operands.split_last() {
+            Some((last, rest)) if last == "]" => operands = rest,
+            _ => {
+                eprintln!("[: missing `]'");
+                return 2;
+            }
+        }
+    }
+    match condition::evaluate(operands) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            eprintln!("{}: {}", args[0], err);
+            2
+        }
+    }
+}
+
+/// Prints the file creation mask in octal, or sets it from an octal `mode`.
+fn umask(_shell: &mut Shell, args: &[String]) -> i32 {
+    let Some(mode) = args.get(1) else {
+        // Reading the mask means setting it, so put it straight back.
+        let mask = stat::umask(Mode::empty());
+        stat::umask(mask);
+        out!("umask", "{:04o}", mask.bits());
+        return 0;
+    };
+    match u32::from_str_radix(mode, 8) {
+        Ok(bits) if bits <= 0o777 => {
+            stat::umask(Mode::from_bits_truncate(bits as _));
+            0
+        }
+        _ => {
+            eprintln!("umask: {}: octal number out of range", mode);
+            1
+        }
+    }
+}
+
+/// Runs a program, sending it SIGTERM once it has run for the given number
+/// of seconds and SIGKILL if it outlives a short grace period after that.
+/// Like coreutils' `timeout`, returns 124 when the time ran out.
+fn timeout(shell: &mut Shell, args: &[String]) -> i32 {
+    let (Some(secs), Some(_)) = (args.get(1), args.get(2)) else {
+        eprintln!("timeout: usage: timeout secs command ...");
+        return 125;
+    };
+    let Ok(secs) = secs.parse::<u64>() else {
+        eprintln!("timeout: invalid time interval '{}'", secs);
+        eprintln!("timeout: usage: timeout secs command ...");
+        return 125;
+    };
+
+    // The command gets a process group of its own, even without job
+    // control, so the signals also reach anything it starts.
+    let child = match unsafe { fork_retrying() } {
+        Ok(ForkResult::Child) => {
+            let _ = setpgid(Pid::from_raw(0), Pid::from_raw(0));
+            shell.jobs.enter_group(None, true);
+            signals::reset_child_signals();
+            run_execvp(&args[2..]);
+        }
+        Ok(ForkResult::Parent { child }) => child,
+        Err(err) => {
+            fork_failed(&args[2..].join(" "), err);
+            return 1;
+        }
+    };
+    let _ = setpgid(child, child);
+
+    let status = match wait_until(child, Instant::now() + Duration::from_secs(secs)) {
+        Some(status) => exit_code(status),
+        None => {
+            let _ = signal::killpg(child, Signal::SIGTERM);
+            if wait_until(child, Instant::now() + Duration::from_secs(1)).is_none() {
+                let _ = signal::killpg(child, Signal::SIGKILL);
+                wait_for(child);
+            }
+            124
+        }
+    };
+    shell.jobs.reclaim_terminal();
+    status
+}
+
+/// Lists the variables, or turns options on with `-o name` or `-X` and off
+/// with `+o name` or `+X`. `-o` alone lists the options.
+fn set(shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        for (name, value) in sorted_vars() {
+            out!("set", "{}={}", name, value);
+        }
+        return 0;
+    }
+
+    let mut args = args[1..].iter();
+    while let Some(arg) = args.next() {
+        let (enable, flags) = match arg.split_at_checked(1) {
+            Some(("-", flags)) if !flags.is_empty() => (true, flags),
+            Some(("+", flags)) if !flags.is_empty() => (false, flags),
+            _ => {
+                eprintln!("set: {}: invalid option", arg);
+                return 2;
+            }
+        };
+        if flags == "o" {
+            let Some(name) = args.next() else {
+                if print_options(shell, enable) != 0 {
+                    return 1;
+                }
+                continue;
+            };
+            let Some(flag) = shell.options.flag(name) else {
+                eprintln!("set: {}: invalid option name", name);
+                return 2;
+            };
+            *flag = enable;
+            continue;
+        }
+        for letter in flags.chars() {
+            let name = ShellOptions::NAMES.iter().find(|(_, c)| *c == letter).map(|(name, _)| *name);
+            let Some(flag) = name.and_then(|name| shell.options.flag(name)) else {
+                eprintln!("set: {}{}: invalid option", &arg[..1], letter);
+                return 2;
+            };
+            *flag = enable;
+        }
+    }
+    0
+}
+
+/// Prints each option and whether it's on, or with `+o`, as the `set`
+/// commands that would restore them.
+fn print_options(shell: &mut Shell, readable: bool) -> i32 {
+    for (name, _) in ShellOptions::NAMES {
+        let on = shell.options.flag(name).is_some_and(|flag| *flag);
+        if readable {
+            out!("set", "{:<15} {}", name, if on { "on" } else { "off" });
+        } else {
+            out!("set", "set {}o {}", if on { '-' } else { '+' }, name);
+        }
+    }
+    0
+}
+
+fn sorted_vars() -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = env::vars().collect();
+    vars.sort();
+    vars
+}
+
+fn unset(_shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        eprintln!("unset: usage: unset name ...");
+        return 2;
+    }
+    let mut status = 0;
+    for name in &args[1..] {
+        if !is_name(name) {
+            eprintln!("unset: '{}': not a valid identifier", name);
+            status = 1;
+            continue;
+        }
+        unsafe { env::remove_var(name) };
+    }
+    status
+}
+
+fn jobs(shell: &mut Shell, _args: &[String]) -> i32 {
+    for line in shell.jobs.list() {
+        out!("jobs", "{}", line);
+    }
+    0
+}
+
+fn send_signal(shell: &mut Shell, args: &[String]) -> i32 {
+    let mut targets = &args[1..];
+    let mut signal = Signal::SIGTERM;
+    if let Some(spec) = targets.first().and_then(|arg| arg.strip_prefix('-')) {
+        let Some(parsed) = parse_signal(spec) else {
+            eprintln!("kill: {}: invalid signal specification", spec);
+            return 1;
+        };
+        signal = parsed;
+        targets = &targets[1..];
+    }
+    if targets.is_empty() {
+        eprintln!("kill: usage: kill [-sig] pid|%job ...");
+        return 2;
+    }
+
+    let mut status = 0;
+    for target in targets {
+        if target.starts_with('%') {
+            let Some(id) = shell.jobs.find(Some(target)).map(|job| job.id) else {
+                eprintln!("kill: {}: no such job", target);
+                status = 1;
+                continue;
+            };
+            if let Err(err) = shell.jobs.signal(id, signal) {
+                eprintln!("kill: {}: {}", target, err.desc());
+                status = 1;
+            }
+        } else if let Ok(pid) = target.parse::<i32>() {
+            if let Err(err) = signal::kill(Pid::from_raw(pid), signal) {
+                eprintln!("kill: ({}) - {}", pid, err.desc());
+                status = 1;
+            }
+        } else {
+            eprintln!("kill: {}: arguments must be process or job IDs", target);
+            status = 1;
+        }
+    }
+    status
+}
+
+/// Waits for the given jobs or processes, returning the status of the last
+/// one, or for every running job when none are given.
+fn wait(shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        for id in shell.jobs.running() {
+            if shell.jobs.wait(id) == Some(128 + Signal::SIGINT as i32) {
+                return 128 + Signal::SIGINT as i32;
+            }
+        }
+        return 0;
+    }
+
+    let mut status = 0;
+    for target in &args[1..] {
+        let id = if target.starts_with('%') {
+            shell.jobs.find(Some(target)).map(|job| job.id)
+        } else if let Ok(pid) = target.parse() {
+            shell.jobs.job_of(Pid::from_raw(pid))
+        } else {
+            eprintln!("wait: {}: not a pid or valid job spec", target);
+            status = 2;
+            continue;
+        };
+        status = match id.and_then(|id| shell.jobs.wait(id)) {
+            Some(code) => code,
+            None if target.starts_with('%') => {
+                eprintln!("wait: {}: no such job", target);
+                127
+            }
+            None => {
+                eprintln!("wait: pid {} is not a child of this shell", target);
+                127
+            }
+        };
+    }
+    status
+}
+
+/// Sets the command run when a signal arrives, `''` to ignore it, or `-`
+/// to go back to the default. EXIT names the shell ending. With no
+/// arguments, lists the traps.
+fn trap(shell: &mut Shell, args: &[String]) -> i32 {
+    if args.len() == 1 {
+        for (&number, command) in &shell.traps {
+            let name = Signal::try_from(number).map_or("EXIT", Signal::as_str);
+            out!("trap", "trap -- '{}' {}", command.replace('\'', "'\\''"), name);
+        }
+        return 0;
+    }
+
+    // A lone signal is reset, like `-` before it.
+    let (command, specs) = match args[1].as_str() {
+        "-" => (None, &args[2..]),
+        _ if args.len() == 2 => (None, &args[1..]),
+        command => (Some(command), &args[2..]),
+    };
+    if specs.is_empty() {
+        eprintln!("trap: usage: trap [command] signal ...");
+        return 2;
+    }
+
+    let mut status = 0;
+    for spec in specs {
+        let number = if spec == "0" || spec.eq_ignore_ascii_case("EXIT") {
+            0
+        } else if let Some(signal) = parse_signal(spec) {
+            let disposition = match command {
+                None => Disposition::Default,
+                Some("") => Disposition::Ignore,
+                Some(_) => Disposition::Catch,
+            };
+            if let Err(err) = signals::set_trap(signal, disposition) {
+                eprintln!("trap: {}: {}", spec, err.desc());
+                status = 1;
+                continue;
+            }
+            signal as i32
+        } else {
+            eprintln!("trap: {}: invalid signal specification", spec);
+            status = 1;
+            continue;
+        };
+        match command {
+            Some(command) => shell.traps.insert(number, command.to_string()),
+            None => shell.traps.remove(&number),
+        };
+    }
+    status
+}
+
+fn fg(shell: &mut Shell, args: &[String]) -> i32 {
+    resume_job(shell, args, true)
+}
+
+fn bg(shell: &mut Shell, args: &[String]) -> i32 {
+    resume_job(shell, args, false)
+}
+
+fn resume_job(shell: &mut Shell, args: &[String], foreground: bool) -> i32 {
+    let spec = args.get(1).map(String::as_str);
+    let Some(id) = shell.jobs.find(spec).map(|job| job.id) else {
+        eprintln!("{}: {}: no such job", args[0], spec.unwrap_or("current"));
+        return 1;
+    };
+    shell.jobs.resume(id, foreground)
+}
+
+/// Interprets the backslash escapes understood by `echo -e`. The returned flag
+/// is set when `\c` cut the output short.
+fn interpret_escapes(text: &str) -> (String, bool) {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('a') => out.push('\x07'),
+            Some('b') => out.push('\x08'),
+            Some('e') => out.push('\x1b'),
+            Some('f') => out.push('\x0c'),
+            Some('v') => out.push('\x0b'),
+            Some('\\') => out.push('\\'),
+            Some('c') => return (out, true),
+            Some('0') => {
+                let mut value = 0u32;
+                for _ in 0..3 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                out.extend(char::from_u32(value));
+            }
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    (out, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nix::unistd::{close, dup, dup2};
+    use std::fs;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::symlink;
+    use std::process;
+    use std::sync::MutexGuard;
+
+    fn lock() -> MutexGuard<'static, ()> {
+        crate::PROCESS_LOCK.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Runs a builtin the way the executor does, by looking it up in the
+    /// table.
+    fn call(shell: &mut Shell, words: &[&str]) -> i32 {
+        let args: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        (find_builtin(words[0]).unwrap().run)(shell, &args)
+    }
+
+    /// Like `call`, with descriptor 1 sent to a file while the builtin runs,
+    /// and what it wrote there returned too.
+    fn capture(shell: &mut Shell, words: &[&str]) -> (i32, String) {
+        let path = env::temp_dir().join(format!("mini-shell-builtins-{}.out", process::id()));
+        let file = File::create(&path).unwrap();
+        io::stdout().flush().unwrap();
+        let saved = dup(1).unwrap();
+        dup2(file.as_raw_fd(), 1).unwrap();
+        let status = call(shell, words);
+        dup2(saved, 1).unwrap();
+        close(saved).unwrap();
+        let output = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        (status, output)
+    }
+
+    #[test]
+    fn echo_flags() {
+        let _lock = lock();
+        let mut shell = Shell::new();
+        assert_eq!(capture(&mut shell, &["echo", "a", "b"]), (0, "a b\n".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "-n", "a"]), (0, "a".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "-e", "a\\tb"]), (0, "a\tb\n".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "a\\tb"]), (0, "a\\tb\n".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "-neE", "a\\tb"]), (0, "a\\tb".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "-e", "a\\cb"]), (0, "a".to_string()));
+        // Anything that isn't only flags is printed, and so is what follows.
+        assert_eq!(capture(&mut shell, &["echo", "-x", "-n"]), (0, "-x -n\n".to_string()));
+        assert_eq!(capture(&mut shell, &["echo", "-"]), (0, "-\n".to_string()));
+    }
+
+    #[test]
+    fn export_sets_and_removes_variables() {
+        let _lock = lock();
+        let mut shell = Shell::new();
+        assert_eq!(call(&mut shell, &["export", "MINI_SHELL_TEST_VAR=a=b"]), 0);
+        assert_eq!(env::var("MINI_SHELL_TEST_VAR").as_deref(), Ok("a=b"));
+        assert_eq!(call(&mut shell, &["export", "MINI_SHELL_TEST_VAR"]), 0);
+        assert_eq!(env::var("MINI_SHELL_TEST_VAR").as_deref(), Ok("a=b"));
+        assert_eq!(call(&mut shell, &["export", "-n", "MINI_SHELL_TEST_VAR"]), 0);
+        assert!(env::var("MINI_SHELL_TEST_VAR").is_err());
+        // The valid names are still exported when another one isn't.
+        assert_eq!(call(&mut shell, &["export", "1X=1", "MINI_SHELL_TEST_VAR=c", "=d"]), 1);
+        assert_eq!(env::var("MINI_SHELL_TEST_VAR").as_deref(), Ok("c"));
+        unsafe { env::remove_var("MINI_SHELL_TEST_VAR") };
+    }
+
+    #[test]
+    fn exit_status() {
+        let mut shell = Shell::new();
+        shell.last_status = 5;
+        assert_eq!(call(&mut shell, &["exit"]), 5);
+        assert!(shell.exiting);
+
+        let mut shell = Shell::new();
+        assert_eq!(call(&mut shell, &["exit", "3"]), 3);
+        assert_eq!(shell.last_status, 3);
+        assert!(shell.exiting);
+
+        let mut shell = Shell::new();
+        assert_eq!(call(&mut shell, &["exit", "x"]), 2);
+        assert_eq!(shell.last_status, 2);
+        assert!(shell.exiting);
+    }
+
+    #[test]
+    fn cd_and_pwd() {
+        let _lock = lock();
+        let start = getcwd().unwrap();
+        let old_pwd = env::var_os("PWD");
+        let old_oldpwd = env::var_os("OLDPWD");
+        let root = env::temp_dir().canonicalize().unwrap().join(format!("mini-shell-cd-{}", process::id()));
+        fs::create_dir_all(root.join("real")).unwrap();
+        symlink(root.join("real"), root.join("link")).unwrap();
+        let root_text = root.display().to_string();
+
+        let mut shell = Shell::new();
+        unsafe {
+            env::set_var("PWD", &start);
+            env::remove_var("OLDPWD");
+        }
+        assert_eq!(capture(&mut shell, &["cd", "-"]), (1, String::new()));
+        assert_eq!(call(&mut shell, &["cd", &root_text]), 0);
+        assert_eq!(env::var("OLDPWD"), Ok(start.display().to_string()));
+        assert_eq!(env::var("PWD"), Ok(root_text.clone()));
+
+        // A symlink stays in `$PWD` unless `-P` resolves it.
+        assert_eq!(call(&mut shell, &["cd", "link"]), 0);
+        assert_eq!(capture(&mut shell, &["pwd"]), (0, format!("{}/link\n", root_text)));
+        assert_eq!(capture(&mut shell, &["pwd", "-P"]), (0, format!("{}/real\n", root_text)));
+        assert_eq!(env::var("OLDPWD"), Ok(root_text.clone()));
+
+        // `cd -` goes back to `$OLDPWD`, printing it, and swaps the two.
+        assert_eq!(capture(&mut shell, &["cd", "-"]), (0, format!("{}\n", root_text)));
+        assert_eq!(getcwd().unwrap(), root);
+        assert_eq!(env::var("OLDPWD"), Ok(format!("{}/link", root_text)));
+        assert_eq!(call(&mut shell, &["cd", ".."]), 0);
+        assert_eq!(env::var("OLDPWD"), Ok(root_text.clone()));
+
+        // A failed `cd` leaves everything where it was.
+        assert_eq!(call(&mut shell, &["cd", &format!("{}/missing", root_text)]), 1);
+        assert_eq!(env::var("OLDPWD"), Ok(root_text.clone()));
+
+        chdir(&start).unwrap();
+        unsafe {
+            match old_pwd {
+                Some(dir) => env::set_var("PWD", dir),
+                None => env::remove_var("PWD"),
+            }
+            match old_oldpwd {
+                Some(dir) => env::set_var("OLDPWD", dir),
+                None => env::remove_var("OLDPWD"),
+            }
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn every_builtin_is_found_by_name() {
+        for builtin in BUILTINS {
+            let found = find_builtin(builtin.name).unwrap();
+            assert_eq!((found.name, found.usage), (builtin.name, builtin.usage));
+        }
+        assert!(find_builtin("ls").is_none());
+        assert!(find_builtin("ECHO").is_none());
+        assert!(find_builtin("").is_none());
+    }
+
+    #[test]
+    fn table_is_sorted_for_help() {
+        for pair in BUILTINS.windows(2) {
+            assert!(pair[0].name < pair[1].name, "{} should come before {}", pair[1].name, pair[0].name);
+        }
+        for builtin in BUILTINS {
+            assert!(builtin.usage.starts_with(builtin.name), "usage of {} names something else", builtin.name);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use nix::unistd::isatty;
+use std::env;
+use std::os::unix::io::RawFd;
+
+const RED: &str = "31";
+
+/// Wraps `text` in the ANSI color `code` for writing to `fd`, unless `fd` isn't
+/// a terminal or `NO_COLOR` is set. The color is reset right after `text`.
+pub fn paint(text: &str, code: &str, fd: RawFd) -> String {
+    if env::var_os("NO_COLOR").is_some() || !isatty(fd).unwrap_or(false) {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+/// Formats an error message for stderr.
+pub fn error(text: &str) -> String {
+    paint(text, RED, 2)
+}
+
+/// The color for the directory in the prompt, from `MINISHELL_PROMPT_COLOR`.
+/// That can be a color name or the digits of an SGR code like `1;32`.
+pub fn prompt_color() -> String {
+    let color = env::var("MINISHELL_PROMPT_COLOR").unwrap_or_default();
+    let code = match color.as_str() {
+        "black" => "30",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "white" => "37",
+        code if !code.is_empty() && code.chars().all(|c| c.is_ascii_digit() || c == ';') => code,
+        _ => "36",
+    };
+    code.to_string()
+}
@@ -0,0 +1,107 @@
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use crate::builtins::BUILTINS;
+use crate::path::is_executable;
+
+/// Tab completion for the line editor: command names for the first word of a
+/// command, file names everywhere else.
+pub struct ShellCompleter;
+
+impl Completer for ShellCompleter {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(is_word_break).map_or(0, |i| i + 1);
+        let word = &line[start..pos];
+        let before = line[..start].trim_end();
+        let command_position = before.is_empty() || before.ends_with(['|', '&', ';', '(']);
+
+        let candidates = if command_position && !word.contains('/') {
+            complete_command(word)
+        } else {
+            complete_path(word)
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for ShellCompleter {}
+
+impl Validator for ShellCompleter {}
+
+impl Helper for ShellCompleter {}
+
+fn is_word_break(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ';' | '|' | '&' | '<' | '>' | '(')
+}
+
+/// Builtins and executables on `$PATH` whose names start with `prefix`.
+fn complete_command(prefix: &str) -> Vec<Pair> {
+    let mut names: BTreeSet<String> = BUILTINS
+        .iter()
+        .map(|builtin| builtin.name.to_string())
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+
+    let path = env::var("PATH").unwrap_or_default();
+    for dir in path.split(':').filter(|dir| !dir.is_empty()) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) {
+                continue;
+            }
+            if is_executable(&entry.path()) {
+                names.insert(name);
+            }
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| Pair { display: name.clone(), replacement: name })
+        .collect()
+}
+
+/// Entries of the directory named by `word` up to its last `/` (the current
+/// directory if it has none) that start with the rest of `word`.
+/// Directories get a trailing `/` so completion can carry on inside them.
+fn complete_path(word: &str) -> Vec<Pair> {
+    let (dir, prefix) = match word.rfind('/') {
+        Some(i) => (&word[..=i], &word[i + 1..]),
+        None => ("", word),
+    };
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<Pair> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let mut name = entry.file_name().to_string_lossy().into_owned();
+            // Like globbing, hidden files only show up when asked for.
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            // Follow symlinks so a link to a directory completes like one.
+            if fs::metadata(entry.path()).is_ok_and(|meta| meta.is_dir()) {
+                name.push('/');
+            }
+            Some(Pair { replacement: format!("{}{}", dir, name), display: name })
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.display.cmp(&b.display));
+    candidates
+}
@@ -0,0 +1,171 @@
+use nix::sys::stat::{lstat, stat, FileStat, Mode, SFlag};
+use nix::unistd::{access, isatty, AccessFlags};
+
+const UNARY: &[&str] = &[
+    "-b", "-c", "-d", "-e", "-f", "-g", "-h", "-k", "-L", "-n", "-p", "-r", "-s", "-S", "-t", "-u", "-w", "-x", "-z",
+];
+const BINARY: &[&str] = &["=", "==", "!=", "<", ">", "-eq", "-ne", "-lt", "-le", "-gt", "-ge", "-nt", "-ot", "-ef"];
+
+/// Evaluates the arguments of `test`, or of `[` without the `]`. As POSIX
+/// says, up to four arguments mean what their number and the operators among
+/// them say, so `test -n` is a non-empty string and `test ! = x` compares;
+/// longer expressions are parsed with `!`, `-a`, `-o` and parentheses, which
+/// bind in that order. The error is a message for an expression that can't
+/// be read.
+pub fn evaluate(args: &[String]) -> Result<bool, String> {
+    let arg = |i: usize| args[i].as_str();
+    match args.len() {
+        0 => Ok(false),
+        1 => Ok(!args[0].is_empty()),
+        2 if arg(0) == "!" => Ok(args[1].is_empty()),
+        2 => unary(arg(0), arg(1)),
+        3 if BINARY.contains(&arg(1)) || arg(1) == "-a" || arg(1) == "-o" => binary(arg(0), arg(1), arg(2)),
+        3 | 4 if arg(0) == "!" => evaluate(&args[1..]).map(|value| !value),
+        3 if arg(0) == "(" && arg(2) == ")" => Ok(!args[1].is_empty()),
+        4 if arg(0) == "(" && arg(3) == ")" => evaluate(&args[1..3]),
+        _ => {
+            let mut parser = Parser { args, pos: 0 };
+            let value = parser.or()?;
+            match args.get(parser.pos) {
+                None => Ok(value),
+                Some(_) => Err("too many arguments".to_string()),
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    args: &'a [String],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self, offset: usize) -> Option<&'a str> {
+        self.args.get(self.pos + offset).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let arg = self.args.get(self.pos).ok_or_else(|| "argument expected".to_string())?;
+        self.pos += 1;
+        Ok(arg)
+    }
+
+    fn or(&mut self) -> Result<bool, String> {
+        let mut value = self.and()?;
+        while self.peek(0) == Some("-o") {
+            self.pos += 1;
+            // Both sides are read either way, to find mistakes in them.
+            value |= self.and()?;
+        }
+        Ok(value)
+    }
+
+    fn and(&mut self) -> Result<bool, String> {
+        let mut value = self.not()?;
+        while self.peek(0) == Some("-a") {
+            self.pos += 1;
+            value &= self.not()?;
+        }
+        Ok(value)
+    }
+
+    fn not(&mut self) -> Result<bool, String> {
+        if self.peek(0) == Some("!") {
+            self.pos += 1;
+            return self.not().map(|value| !value);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<bool, String> {
+        if self.peek(0) == Some("(") {
+            self.pos += 1;
+            let value = self.or()?;
+            if self.next().ok() != Some(")") {
+                return Err("`)' expected".to_string());
+            }
+            return Ok(value);
+        }
+        if let Some(op) = self.peek(1)
+            && BINARY.contains(&op)
+        {
+            let left = self.next()?;
+            self.pos += 1;
+            let right = self.next()?;
+            return binary(left, op, right);
+        }
+        let arg = self.next()?;
+        if UNARY.contains(&arg) && self.peek(0).is_some() {
+            let operand = self.next()?;
+            return unary(arg, operand);
+        }
+        Ok(!arg.is_empty())
+    }
+}
+
+fn unary(op: &str, operand: &str) -> Result<bool, String> {
+    let file_type = |kind: SFlag| stat(operand).is_ok_and(|st| st.st_mode & SFlag::S_IFMT.bits() == kind.bits());
+    let mode_bit = |bit: Mode| stat(operand).is_ok_and(|st| st.st_mode & bit.bits() != 0);
+    Ok(match op {
+        "-n" => !operand.is_empty(),
+        "-z" => operand.is_empty(),
+        "-e" => stat(operand).is_ok(),
+        "-f" => file_type(SFlag::S_IFREG),
+        "-d" => file_type(SFlag::S_IFDIR),
+        "-b" => file_type(SFlag::S_IFBLK),
+        "-c" => file_type(SFlag::S_IFCHR),
+        "-p" => file_type(SFlag::S_IFIFO),
+        "-S" => file_type(SFlag::S_IFSOCK),
+        "-h" | "-L" => lstat(operand).is_ok_and(|st| st.st_mode & SFlag::S_IFMT.bits() == SFlag::S_IFLNK.bits()),
+        "-s" => stat(operand).is_ok_and(|st| st.st_size > 0),
+        "-g" => mode_bit(Mode::S_ISGID),
+        "-u" => mode_bit(Mode::S_ISUID),
+        "-k" => mode_bit(Mode::S_ISVTX),
+        "-r" => access(operand, AccessFlags::R_OK).is_ok(),
+        "-w" => access(operand, AccessFlags::W_OK).is_ok(),
+        "-x" => access(operand, AccessFlags::X_OK).is_ok(),
+        "-t" => isatty(integer(operand)? as i32).unwrap_or(false),
+        _ => return Err(format!("{}: unary operator expected", op)),
+    })
+}
+
+fn binary(left: &str, op: &str, right: &str) -> Result<bool, String> {
+    Ok(match op {
+        "=" | "==" => left == right,
+        "!=" => left != right,
+        "<" => left < right,
+        ">" => left > right,
+        "-a" => !left.is_empty() && !right.is_empty(),
+        "-o" => !left.is_empty() || !right.is_empty(),
+        "-eq" => integer(left)? == integer(right)?,
+        "-ne" => integer(left)? != integer(right)?,
+        "-lt" => integer(left)? < integer(right)?,
+        "-le" => integer(left)? <= integer(right)?,
+        "-gt" => integer(left)? > integer(right)?,
+        "-ge" => integer(left)? >= integer(right)?,
+        // A file that exists is newer than one that doesn't.
+        "-nt" => match (stat(left), stat(right)) {
+            (Ok(left), Ok(right)) => modified(&left) > modified(&right),
+            (left, _) => left.is_ok(),
+        },
+        "-ot" => match (stat(left), stat(right)) {
+            (Ok(left), Ok(right)) => modified(&left) < modified(&right),
+            (_, right) => right.is_ok(),
+        },
+        "-ef" => match (stat(left), stat(right)) {
+            (Ok(left), Ok(right)) => (left.st_dev, left.st_ino) == (right.st_dev, right.st_ino),
+            _ => false,
+        },
+        _ => return Err(format!("{}: binary operator expected", op)),
+    })
+}
+
+fn modified(st: &FileStat) -> (i64, i64) {
+    (st.st_mtime, st.st_mtime_nsec)
+}
+
+/// Reads an operand of an integer comparison, which may have a sign and
+/// surrounding blanks.
+fn integer(text: &str) -> Result<i64, String> {
+    text.trim().parse().map_err(|_| format!("{}: integer expression expected", text))
+}
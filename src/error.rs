@@ -0,0 +1,44 @@
+use nix::errno::Errno;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ShellError {
+    Syntax(String),
+    Redirect(String, Errno),
+    NulByte,
+    AmbiguousRedirect(String),
+    /// `>` onto an existing file with noclobber set.
+    Clobber(String),
+    Arithmetic(String),
+    /// An unset variable expanded with nounset set.
+    Unbound(String),
+    /// A control operator with no command where one belongs.
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShellError::Syntax(msg) => write!(f, "mini-shell: syntax error: {}", msg),
+            ShellError::Redirect(target, err) => write!(f, "mini-shell: {}: {}", target, err.desc()),
+            ShellError::NulByte => write!(f, "mini-shell: invalid argument containing NUL byte"),
+            ShellError::AmbiguousRedirect(target) => write!(f, "mini-shell: {}: ambiguous redirect", target),
+            ShellError::Clobber(target) => write!(f, "mini-shell: {}: cannot overwrite existing file", target),
+            ShellError::Arithmetic(msg) => write!(f, "mini-shell: {}", msg),
+            ShellError::Unbound(name) => write!(f, "mini-shell: {}: unbound variable", name),
+            ShellError::UnexpectedToken(token) => write!(f, "mini-shell: syntax error near unexpected token `{}'", token),
+        }
+    }
+}
+
+impl std::error::Error for ShellError {}
+
+/// Describes an I/O error the way the C library would, without the
+/// `(os error N)` that `io::Error` adds.
+pub fn describe(err: &io::Error) -> String {
+    match err.raw_os_error() {
+        Some(code) => Errno::from_i32(code).desc().to_string(),
+        None => err.to_string(),
+    }
+}
@@ -1,18 +1,329 @@
-use nix::unistd::execvp;
+use nix::errno::Errno;
+use nix::unistd::{execv, execvp, ForkResult};
 use std::ffi::CString;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStringExt;
+use std::path::Path;
+use std::env;
+use crate::builtins::{find_builtin, try_run_builtin, Builtin};
+use crate::color;
+use crate::error::ShellError;
+use crate::expand::{expand_words, parse_assignment};
+use crate::history::History;
+use crate::jobs::{fork_failed, fork_retrying, JobState};
+use crate::parser::{Connector, Node, SimpleCommand};
+use crate::path::find_in_path;
+use crate::pipeline::run_pipeline;
+use crate::redirect::{apply_redirections, save_fds, Redirection};
+use crate::shell::Shell;
+use crate::substitute::finish_processes;
+use crate::signals;
 
-pub fn run_execvp(command: &str) -> ! {
-     let tokens: Vec<&str>= command.split_ascii_whitespace().collect();
-    let cstr_args: Vec<CString> =
-        tokens.iter().map(|&s| CString::new(s).unwrap()).collect();
-    let prog = &cstr_args[0];
-    let args = &cstr_args[..];
+/// A simple command with its words expanded, ready to run.
+pub struct Command {
+    pub assignments: Vec<(String, String)>,
+    pub argv: Vec<String>,
+    pub redirections: Vec<Redirection>,
+}
 
-    match execvp(prog, args) {
+/// Expands the words of `command`, setting aside its prefix assignments.
+pub fn expand_command(command: &SimpleCommand, shell: &Shell) -> Result<Command, ShellError> {
+    let mut argv = expand_words(&command.words, shell)?;
+
+    let count = argv.iter().take_while(|arg| parse_assignment(arg).is_some()).count();
+    let assignments = argv
+        .drain(..count)
+        .filter_map(|arg| {
+            parse_assignment(&arg).map(|(name, value)| (name.to_string(), value.to_string()))
+        })
+        .collect::<Vec<_>>();
+    // The environment can't hold a NUL, so a value with one that made it
+    // this far, say from a script, isn't assigned.
+    if assignments.iter().any(|(_, value)| value.contains('\0')) {
+        return Err(ShellError::NulByte);
+    }
+
+    Ok(Command { assignments, argv, redirections: command.redirections.clone() })
+}
+
+/// Runs a parsed line and returns the status of the last command that ran,
+/// which is also left in `$?`.
+pub fn run(node: &Node, shell: &mut Shell) -> i32 {
+    let status = match node {
+        Node::Command(command) => run_simple(command, false, shell),
+        Node::Pipeline(stages) => run_pipeline(stages, &node.to_string(), false, shell),
+        Node::AndOr(left, connector, right) => {
+            // The left side's status is being tested, so it failing doesn't
+            // trip errexit.
+            let errexit = std::mem::replace(&mut shell.options.errexit, false);
+            let status = run(left, shell);
+            shell.options.errexit = errexit;
+            if shell.exiting {
+                return status;
+            }
+            let proceed = match connector {
+                Connector::And => status == 0,
+                Connector::Or => status != 0,
+            };
+            return if proceed { run(right, shell) } else { status };
+        }
+        Node::Sequence(nodes) => {
+            // Once the shell is exiting, nothing after that runs.
+            for node in nodes {
+                if shell.exiting {
+                    break;
+                }
+                run(node, shell);
+            }
+            return shell.last_status;
+        }
+        Node::Background(inner) => match inner.as_ref() {
+            Node::Command(command) => run_simple(command, true, shell),
+            Node::Pipeline(stages) => run_pipeline(stages, &inner.to_string(), true, shell),
+            _ => run_in_background(inner, shell),
+        },
+    };
+    finish_processes(!matches!(node, Node::Background(_)));
+    shell.last_status = status;
+    if status != 0 && shell.options.errexit && !matches!(node, Node::Background(_)) {
+        shell.exiting = true;
+    }
+    status
+}
+
+/// Prints `command` for xtrace, quoting words that wouldn't read back as
+/// one.
+fn trace(command: &Command, shell: &Shell) {
+    if !shell.options.xtrace {
+        return;
+    }
+    let assignments = command.assignments.iter().map(|(name, value)| format!("{}={}", name, quote(value)));
+    let words: Vec<String> = assignments.chain(command.argv.iter().map(|arg| quote(arg))).collect();
+    eprintln!("+ {}", words.join(" "));
+}
+
+fn quote(word: &str) -> String {
+    if !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%^".contains(c)) {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+fn run_simple(command: &SimpleCommand, background: bool, shell: &mut Shell) -> i32 {
+    let text = &command.text;
+    let command = match expand_command(command, shell) {
+        Ok(command) => command,
+        Err(err) => return report(&err, 2, shell),
+    };
+    trace(&command, shell);
+    let args = &command.argv;
+
+    if args.is_empty() {
+        for (name, value) in &command.assignments {
+            unsafe { env::set_var(name, value) };
+        }
+        // Redirections without a command still open their files, like
+        // `> out.txt` truncating it; a child does that and exits.
+        if command.redirections.is_empty() {
+            return if command.assignments.is_empty() { shell.last_status } else { 0 };
+        }
+    }
+
+    // A builtin in the background runs in a forked copy of the shell, like
+    // any other command, so it can't hold up or change the shell itself.
+    if !background && let Some(builtin) = args.first().and_then(|name| find_builtin(name)) {
+        return run_builtin(builtin, &command, shell);
+    }
+
+    match unsafe { fork_retrying() } {
+        Ok(ForkResult::Child) => {
+            shell.jobs.enter_group(None, !background);
+            signals::reset_child_signals();
+            // As in a pipeline stage, an `exit` here is the child's own.
+            shell.traps.clear();
+            shell.history = History::default();
+            exec_command(&command, shell);
+        }
+        Ok(ForkResult::Parent { child }) => {
+            shell.jobs.assign_group(child, None);
+            if background {
+                let job = shell.jobs.add(child, vec![child], text, JobState::Running);
+                shell.last_background = Some(child);
+                println!("[{}] {}", job, child);
+                0
+            } else {
+                shell.jobs.wait_foreground(child, vec![child], text)
+            }
+        }
+        Err(err) => {
+            fork_failed(text, err);
+            1
+        }
+    }
+}
+
+/// Runs a builtin in the shell itself, with the command's redirections
+/// applied only until it returns.
+fn run_builtin(builtin: &Builtin, command: &Command, shell: &mut Shell) -> i32 {
+    if command.redirections.is_empty() {
+        return (builtin.run)(shell, &command.argv);
+    }
+    // Output still buffered for the old stdout has to go there first.
+    let _ = io::stdout().flush();
+    // `exec` without a command applies them to the shell for good.
+    if builtin.name == "exec" && command.argv.len() == 1 {
+        return match apply_redirections(&command.redirections, shell) {
+            Ok(()) => 0,
+            Err(err) => report(&err, 1, shell),
+        };
+    }
+    let saved = save_fds(&command.redirections);
+    let status = match apply_redirections(&command.redirections, shell) {
+        Ok(()) => (builtin.run)(shell, &command.argv),
+        Err(err) => report(&err, 1, shell),
+    };
+    let _ = io::stdout().flush();
+    saved.restore();
+    status
+}
+
+/// Prints the error that kept a command from running and returns `status`
+/// for it. An unbound variable under nounset also ends a shell that isn't
+/// interactive, with status 2, as in sh.
+fn report(err: &ShellError, status: i32, shell: &mut Shell) -> i32 {
+    eprintln!("{}", err);
+    if matches!(err, ShellError::Unbound(_)) && !shell.interactive {
+        shell.exiting = true;
+        return 2;
+    }
+    status
+}
+
+/// Runs a list like `a && b` as a background job of its own, in a forked
+/// copy of the shell.
+fn run_in_background(node: &Node, shell: &mut Shell) -> i32 {
+    match unsafe { fork_retrying() } {
+        Ok(ForkResult::Child) => {
+            shell.jobs.enter_group(None, false);
+            signals::reset_child_signals();
+            let mut subshell = shell.subshell();
+            let status = run(node, &mut subshell);
+            let _ = io::stdout().flush();
+            std::process::exit(status);
+        }
+        Ok(ForkResult::Parent { child }) => {
+            shell.jobs.assign_group(child, None);
+            let job = shell.jobs.add(child, vec![child], &node.to_string(), JobState::Running);
+            shell.last_background = Some(child);
+            println!("[{}] {}", job, child);
+            0
+        }
+        Err(err) => {
+            fork_failed(&node.to_string(), err);
+            1
+        }
+    }
+}
+
+/// Runs one stage of a pipeline in the forked child meant for it.
+pub fn run_stage(command: &SimpleCommand, shell: &mut Shell) -> ! {
+    // Like a subshell, a stage doesn't run the shell's traps, and an `exit`
+    // in it doesn't save the history.
+    shell.traps.clear();
+    shell.history = History::default();
+    match expand_command(command, shell) {
+        Ok(command) => {
+            trace(&command, shell);
+            exec_command(&command, shell)
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Applies a command's prefix assignments and redirections to the current
+/// process, then replaces it with the program. A builtin runs right here
+/// instead, and the process exits with its status.
+pub fn exec_command(command: &Command, shell: &mut Shell) -> ! {
+    // Prefix assignments only apply to this command's environment.
+    for (name, value) in &command.assignments {
+        unsafe { env::set_var(name, value) };
+    }
+    if let Err(err) = apply_redirections(&command.redirections, shell) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+    if command.argv.is_empty() {
+        std::process::exit(0);
+    }
+    if let Some(status) = try_run_builtin(shell, &command.argv) {
+        let _ = io::stdout().flush();
+        std::process::exit(status);
+    }
+    run_execvp(&command.argv);
+}
+
+pub fn run_execvp(tokens: &[String]) -> ! {
+    let cstr_args = match to_cstrings(tokens) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let Some(prog) = cstr_args.first() else {
+        std::process::exit(0);
+    };
+
+    // Like sh: 127 when there's no such program, 126 when it can't be run.
+    match execvp(prog, &cstr_args) {
         Ok(_) => unreachable!(),
+        Err(Errno::ENOENT) if !tokens[0].contains('/') => {
+            eprintln!("{}", color::error(&format!("mini-shell: {}: command not found", tokens[0])));
+            std::process::exit(127);
+        }
+        Err(Errno::ENOENT) => {
+            eprintln!("{}", color::error(&format!("mini-shell: {}: No such file or directory", tokens[0])));
+            std::process::exit(127);
+        }
+        // A file the kernel can't run, such as a script without a `#!`
+        // line, is handed to sh the way sh itself would.
+        Err(Errno::ENOEXEC) => {
+            let path = find_in_path(&tokens[0])
+                .map_or_else(|| tokens[0].clone().into_bytes(), |path| path.into_os_string().into_vec());
+            let mut sh_args = vec![c"/bin/sh".to_owned()];
+            sh_args.extend(CString::new(path).ok());
+            sh_args.extend(cstr_args[1..].iter().cloned());
+            let err = execv(c"/bin/sh", &sh_args).unwrap_err();
+            eprintln!("{}", color::error(&format!("Execution failed: {}", err)));
+            std::process::exit(126);
+        }
+        // The kernel says EACCES for directories too.
+        Err(Errno::EACCES | Errno::EISDIR) => {
+            let reason = if tokens[0].contains('/') && Path::new(&tokens[0]).is_dir() {
+                "is a directory"
+            } else {
+                "Permission denied"
+            };
+            eprintln!("{}", color::error(&format!("mini-shell: {}: {}", tokens[0], reason)));
+            std::process::exit(126);
+        }
+        Err(err @ (Errno::ENOTDIR | Errno::ELOOP | Errno::ENAMETOOLONG | Errno::E2BIG | Errno::ETXTBSY)) => {
+            eprintln!("{}", color::error(&format!("mini-shell: {}: {}", tokens[0], err.desc())));
+            std::process::exit(126);
+        }
         Err(err) => {
-            eprintln!("Execution failed: {}", err);
+            eprintln!("{}", color::error(&format!("Execution failed: {}", err)));
             std::process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
+
+fn to_cstrings(tokens: &[String]) -> Result<Vec<CString>, ShellError> {
+    tokens
+        .iter()
+        .map(|s| CString::new(s.as_str()).map_err(|_| ShellError::NulByte))
+        .collect()
+}
@@ -0,0 +1,257 @@
+use nix::unistd::User;
+use std::env;
+use crate::arith::evaluate;
+use crate::brace::expand_braces;
+use crate::error::ShellError;
+use crate::glob::{escape, expand_glob};
+use crate::shell::Shell;
+use crate::substitute::{command_output, process_substitution};
+use crate::tokenizer::{arithmetic_len, split_substitution, Quote, Substitution, Word};
+
+/// Expands each word and flattens the results into an argument list. Brace
+/// expansion comes first, so each word it makes is expanded on its own.
+pub fn expand_words(words: &[Word], shell: &Shell) -> Result<Vec<String>, ShellError> {
+    let mut args = Vec::new();
+    for word in words.iter().flat_map(expand_braces) {
+        args.extend(expand_word(&word, shell)?);
+    }
+    Ok(args)
+}
+
+/// Applies tilde, variable, command and process substitution and glob
+/// expansion to a single word. Quoted segments are protected from the expansions their
+/// quoting disables. The values of unquoted variables and command
+/// substitutions are split into several words on `$IFS`, except in the value
+/// of a `NAME=value` word.
+pub fn expand_word(word: &Word, shell: &Shell) -> Result<Vec<String>, ShellError> {
+    let assignment = word.segments.first().is_some_and(|segment| {
+        segment.quote == Quote::None && segment.substitution.is_none() && parse_assignment(&segment.text).is_some()
+    });
+    let mut patterns = Vec::new();
+    let mut pattern: Option<String> = None;
+    for (i, segment) in word.segments.iter().enumerate() {
+        match (segment.quote, segment.substitution) {
+            (_, Some(kind @ (Substitution::Input | Substitution::Output))) => {
+                let path = process_substitution(&segment.text, kind == Substitution::Output, shell);
+                pattern.get_or_insert_default().push_str(&escape(&path));
+            }
+            (Quote::Single, _) => pattern.get_or_insert_default().push_str(&escape(&segment.text)),
+            (Quote::Double, None) => {
+                pattern.get_or_insert_default().push_str(&escape(&expand_vars(&segment.text, shell)?));
+            }
+            (_, Some(_)) if segment.quote == Quote::Double || assignment => {
+                pattern.get_or_insert_default().push_str(&escape(&command_output(&segment.text, shell)));
+            }
+            (Quote::None, None) => {
+                let text = if i == 0 { expand_tilde(&segment.text) } else { segment.text.clone() };
+                for (text, expanded) in expand_parts(&text, shell)? {
+                    if expanded && !assignment {
+                        split_fields(&text, &mut pattern, &mut patterns);
+                    } else if !text.is_empty() {
+                        pattern.get_or_insert_default().push_str(&text.replace('\\', "\\\\"));
+                    }
+                }
+            }
+            (_, Some(_)) => split_fields(&command_output(&segment.text, shell), &mut pattern, &mut patterns),
+        }
+    }
+    patterns.extend(pattern);
+    Ok(patterns.iter().flat_map(|pattern| expand_glob(pattern)).collect())
+}
+
+/// Replaces a leading `~` with `$HOME` and a leading `~user` with that user's
+/// home directory. A tilde anywhere else in the word is left alone.
+pub fn expand_tilde(word: &str) -> String {
+    let Some(rest) = word.strip_prefix('~') else {
+        return word.to_string();
+    };
+    let (user, suffix) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+    let home = if user.is_empty() {
+        env::var("HOME").ok()
+    } else {
+        User::from_name(user)
+            .ok()
+            .flatten()
+            .map(|user| user.dir.display().to_string())
+    };
+
+    match home {
+        Some(home) => format!("{}{}", home, suffix),
+        None => word.to_string(),
+    }
+}
+
+/// Replaces `$NAME` and `${NAME}` references in `word` with their environment
+/// values, `$?` with the last exit status, `$$` with the shell's process ID,
+/// `$!` with that of the last background command, `$0` to `$9` (or `${10}`
+/// and up) with the positional parameters, `$#` with their count and `$@` or
+/// `$*` with all of them joined by spaces. Undefined variables expand to an
+/// empty string, and a `$` that isn't followed by a name is kept as-is.
+/// `$((...))` is replaced by the value of the arithmetic expression inside it.
+pub fn expand_vars(word: &str, shell: &Shell) -> Result<String, ShellError> {
+    Ok(expand_parts(word, shell)?.into_iter().map(|(text, _)| text).collect())
+}
+
+/// Does the work of `expand_vars`, but keeps the literal text and the value
+/// of each expansion apart as `(text, expanded)` pairs, since only the
+/// values are split into fields.
+fn expand_parts(word: &str, shell: &Shell) -> Result<Vec<(String, bool)>, ShellError> {
+    let mut parts = Vec::new();
+    let mut rest = word;
+
+    while let Some(pos) = rest.find('$') {
+        parts.push((rest[..pos].to_string(), false));
+        let after = &rest[pos + 1..];
+
+        if let Some(len) = arithmetic_len(&rest[pos..]) {
+            let expression = &rest[pos + 3..pos + len - 2];
+            parts.push((evaluate(&expand_vars(expression, shell)?)?.to_string(), true));
+            rest = &rest[pos + len..];
+            continue;
+        }
+
+        if let Some(braced) = after.strip_prefix('{')
+            && let Some(end) = braced.find('}')
+        {
+            parts.push((lookup(&braced[..end], shell)?, true));
+            rest = &braced[end + 1..];
+            continue;
+        }
+
+        let len = if after.starts_with(|c: char| c.is_ascii_digit() || "?#@*$!".contains(c)) {
+            1
+        } else {
+            name_len(after)
+        };
+        if len == 0 {
+            parts.push(("$".to_string(), false));
+        } else {
+            parts.push((lookup(&after[..len], shell)?, true));
+        }
+        rest = &after[len..];
+    }
+
+    parts.push((rest.to_string(), false));
+    Ok(parts)
+}
+
+/// Splits `text`, the value of an unquoted expansion, into fields on the
+/// characters of `$IFS`. The first field continues `pattern` and each later
+/// one starts a new pattern; whitespace in IFS around the fields is dropped,
+/// while each other IFS character ends a field, even an empty one.
+fn split_fields(text: &str, pattern: &mut Option<String>, patterns: &mut Vec<String>) {
+    let ifs = env::var("IFS").unwrap_or_else(|_| " \t\n".to_string());
+    // Wildcards produced by unquoted expansions stay live, as in sh, but
+    // backslashes in them are just text.
+    let text = text.replace('\\', "\\\\");
+    let mut after_space = false;
+    for c in text.chars() {
+        if !ifs.contains(c) {
+            pattern.get_or_insert_default().push(c);
+            after_space = false;
+        } else if c.is_whitespace() {
+            patterns.extend(pattern.take());
+            after_space = true;
+        } else {
+            match pattern.take() {
+                Some(field) => patterns.push(field),
+                None if !after_space => patterns.push(String::new()),
+                None => {}
+            }
+            after_space = false;
+        }
+    }
+}
+
+/// Expands a here-document body much as double quotes would: variables and
+/// command substitutions are replaced, and a backslash escapes `$`, `` ` ``,
+/// `\` and a newline. Quote characters are ordinary text.
+pub fn expand_heredoc(body: &str, shell: &Shell) -> Result<String, ShellError> {
+    let mut out = String::new();
+    // Text waiting for variable expansion, which has to stop at escapes.
+    let mut text = String::new();
+    let mut chars = body.chars();
+
+    loop {
+        if let Some(Ok((command, rest))) = split_substitution(chars.as_str()) {
+            out.push_str(&expand_vars(&std::mem::take(&mut text), shell)?);
+            out.push_str(&command_output(&command, shell));
+            chars = rest.chars();
+            continue;
+        }
+        let Some(c) = chars.next() else {
+            break;
+        };
+        match (c, chars.clone().next()) {
+            ('\\', Some(next @ ('$' | '`' | '\\'))) => {
+                out.push_str(&expand_vars(&std::mem::take(&mut text), shell)?);
+                out.push(next);
+                chars.next();
+            }
+            ('\\', Some('\n')) => {
+                chars.next();
+            }
+            _ => text.push(c),
+        }
+    }
+    out.push_str(&expand_vars(&text, shell)?);
+    Ok(out)
+}
+
+/// Returns the name and value of a `NAME=value` assignment word.
+pub fn parse_assignment(word: &str) -> Option<(&str, &str)> {
+    let (name, value) = word.split_once('=')?;
+    if !is_name(name) {
+        return None;
+    }
+    Some((name, value))
+}
+
+/// Whether `s` can name a variable.
+pub fn is_name(s: &str) -> bool {
+    !s.is_empty() && name_len(s) == s.len()
+}
+
+fn name_len(s: &str) -> usize {
+    if !s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return 0;
+    }
+    s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(s.len())
+}
+
+/// The value of a variable or parameter. With nounset set, one that isn't
+/// set is an error, though `$@` and `$*` may always be empty.
+fn lookup(name: &str, shell: &Shell) -> Result<String, ShellError> {
+    let value = match name {
+        "?" => Some(shell.last_status.to_string()),
+        "$" => Some(shell.pid.to_string()),
+        "!" => shell.last_background.map(|pid| pid.to_string()),
+        "#" => Some(shell.positional.len().saturating_sub(1).to_string()),
+        "@" | "*" => Some(shell.positional.get(1..).unwrap_or_default().join(" ")),
+        _ if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) => {
+            name.parse().ok().and_then(|n: usize| shell.positional.get(n).cloned())
+        }
+        _ => env::var(name).ok(),
+    };
+    match value {
+        Some(value) => Ok(value),
+        None if shell.options.nounset => Err(ShellError::Unbound(name.to_string())),
+        None => Ok(String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_names() {
+        for name in ["a", "_", "_x1", "PATH", "snake_case"] {
+            assert!(is_name(name), "{:?} should be a name", name);
+        }
+        for name in ["", "1a", "a-b", "a=b", "=foo", "a b", "$a", "é"] {
+            assert!(!is_name(name), "{:?} shouldn't be a name", name);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::Path;
+
+/// Expands `*`, `?` and `[...]` in `word` against the filesystem, returning the
+/// sorted matches. A backslash makes the following character literal. A word
+/// without wildcards, or one that matches nothing, is returned unescaped.
+pub fn expand_glob(word: &str) -> Vec<String> {
+    if !has_wildcard(word) {
+        return vec![unescape(word)];
+    }
+
+    let segments: Vec<&str> = word.split('/').collect();
+    let mut paths = vec![String::new()];
+    for (i, segment) in segments.iter().enumerate() {
+        let separator = if i < segments.len() - 1 { "/" } else { "" };
+        let mut next = Vec::new();
+        for base in &paths {
+            if !has_wildcard(segment) {
+                next.push(format!("{}{}{}", base, unescape(segment), separator));
+                continue;
+            }
+            let dir = if base.is_empty() { "." } else { base.as_str() };
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                // Hidden files only match a pattern that names the dot explicitly.
+                if name.starts_with('.') && !segment.starts_with('.') {
+                    continue;
+                }
+                if matches(segment, &name) {
+                    next.push(format!("{}{}{}", base, name, separator));
+                }
+            }
+        }
+        paths = next;
+    }
+
+    paths.retain(|path| Path::new(path).symlink_metadata().is_ok());
+    if paths.is_empty() {
+        return vec![unescape(word)];
+    }
+    paths.sort();
+    paths
+}
+
+fn has_wildcard(word: &str) -> bool {
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' | '[' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Backslash-escapes every character that is special in a pattern.
+pub fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn unescape(pattern: &str) -> String {
+    let mut out = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reports whether `name` matches the shell pattern `pattern`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_from(&pattern, &name)
+}
+
+fn matches_from(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| matches_from(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && matches_from(&pattern[1..], &name[1..]),
+        Some('\\') if pattern.len() > 1 => {
+            name.first() == Some(&pattern[1]) && matches_from(&pattern[2..], &name[1..])
+        }
+        Some('[') => match (name.first(), parse_class(&pattern[1..])) {
+            (Some(&c), Some((class, len))) => {
+                class.contains(c) && matches_from(&pattern[len + 1..], &name[1..])
+            }
+            (Some(&c), None) => c == '[' && matches_from(&pattern[1..], &name[1..]),
+            (None, _) => false,
+        },
+        Some(&c) => name.first() == Some(&c) && matches_from(&pattern[1..], &name[1..]),
+    }
+}
+
+struct CharClass {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    fn contains(&self, c: char) -> bool {
+        let found = self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi);
+        found != self.negated
+    }
+}
+
+/// Parses the body of a `[...]` class, returning it and the number of pattern
+/// characters consumed including the closing `]`.
+fn parse_class(pattern: &[char]) -> Option<(CharClass, usize)> {
+    let mut i = 0;
+    let negated = matches!(pattern.first(), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let start = i;
+    while i < pattern.len() {
+        let c = pattern[i];
+        // A `]` right after the opening bracket is a literal member.
+        if c == ']' && i > start {
+            return Some((CharClass { negated, ranges }, i + 1));
+        }
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&hi| hi != ']') {
+            ranges.push((c, pattern[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+    None
+}
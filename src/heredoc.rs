@@ -0,0 +1,106 @@
+use crate::tokenizer::{find_operators, line_state, strip_comments, tokenize, LineState};
+
+/// The text of a `<<` here-document.
+#[derive(Clone)]
+pub struct HereDoc {
+    pub body: String,
+    /// Whether expansions apply to the body, which is the case unless any
+    /// part of the delimiter was quoted.
+    pub expand: bool,
+}
+
+/// The commands in some input with their here-document bodies taken out.
+pub struct Commands {
+    /// The command lines, comments removed, where each `<< DELIM` has become
+    /// `<<N`, `N` being the number of its body.
+    pub text: String,
+    pub heredocs: Vec<HereDoc>,
+    /// False if the input ran out before a here-document's delimiter.
+    pub complete: bool,
+}
+
+/// Splits `input` into command lines and the here-document bodies that
+/// follow them. Each body is numbered from `first` so that bodies gathered
+/// by nested runs don't collide.
+pub fn take_heredocs(input: &str, first: usize) -> Commands {
+    let mut commands = Commands { text: String::new(), heredocs: Vec::new(), complete: true };
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        // A quoted newline doesn't end the line, so the `<<` operators and the
+        // bodies they introduce are only looked for once every quote closes.
+        let mut line = String::new();
+        loop {
+            let (next, after) = split_line(rest);
+            line.push_str(next);
+            rest = after;
+            if rest.is_empty() || !matches!(line_state(&strip_comments(&line)), LineState::OpenQuote) {
+                break;
+            }
+        }
+        let line = strip_comments(&line);
+
+        let mut start = 0;
+        for (pos, op) in find_operators(&line, &["<<<", "<<-", "<<"]) {
+            if op == "<<<" {
+                continue;
+            }
+            // A missing delimiter is left for the redirection parser to report.
+            let Some((delimiter, quoted, len)) = delimiter_word(&line[pos + op.len()..]) else {
+                continue;
+            };
+
+            let (body, after, found) = read_body(rest, &delimiter, op == "<<-");
+            rest = after;
+            commands.complete &= found;
+            commands.text.push_str(&line[start..pos]);
+            commands.text.push_str(&format!("<<{} ", first + commands.heredocs.len()));
+            commands.heredocs.push(HereDoc { body, expand: !quoted });
+            start = pos + op.len() + len;
+        }
+        commands.text.push_str(&line[start..]);
+    }
+    commands
+}
+
+/// Splits the first line, including its newline, off `text`.
+fn split_line(text: &str) -> (&str, &str) {
+    match text.find('\n') {
+        Some(end) => text.split_at(end + 1),
+        None => (text, ""),
+    }
+}
+
+/// Reads the delimiter word at the start of `text`, returning it with its
+/// quoting removed, whether any of it was quoted, and how much of `text` it
+/// took up.
+fn delimiter_word(text: &str) -> Option<(String, bool, usize)> {
+    let trimmed = text.trim_start_matches([' ', '\t']);
+    let end = find_operators(trimmed, &[" ", "\t", "\n", ";", "|", "&", "<", ">", "(", ")"])
+        .first()
+        .map_or(trimmed.len(), |&(pos, _)| pos);
+    let raw = &trimmed[..end];
+
+    let words = tokenize(raw).ok()?;
+    let delimiter = words.first()?.segments.iter().map(|segment| segment.text.as_str()).collect();
+    let quoted = raw.contains(['\'', '"', '\\']);
+    Some((delimiter, quoted, text.len() - trimmed.len() + end))
+}
+
+/// Reads body lines from `text` up to a line matching `delimiter`, returning
+/// the body, the text after the delimiter line, and whether the delimiter was
+/// found. With `strip_tabs`, leading tabs are removed from every line, as
+/// `<<-` asks.
+fn read_body<'a>(mut text: &'a str, delimiter: &str, strip_tabs: bool) -> (String, &'a str, bool) {
+    let mut body = String::new();
+    while !text.is_empty() {
+        let (line, after) = split_line(text);
+        text = after;
+        let line = if strip_tabs { line.trim_start_matches('\t') } else { line };
+        if line.trim_end_matches('\n') == delimiter {
+            return (body, text, true);
+        }
+        body.push_str(line);
+    }
+    (body, text, false)
+}
@@ -0,0 +1,141 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many entries are kept when `HISTSIZE` isn't set.
+const DEFAULT_SIZE: usize = 1000;
+
+/// Commands entered during the session, plus those loaded from the history
+/// file when one is in use.
+#[derive(Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// Loads the history file named by `HISTFILE`, or `~/.mini_shell_history`,
+    /// and remembers it so that `save` writes back to the same place.
+    pub fn load(&mut self) {
+        let path = match env::var_os("HISTFILE") {
+            Some(path) => PathBuf::from(path),
+            None => match env::var_os("HOME") {
+                Some(home) => PathBuf::from(home).join(".mini_shell_history"),
+                None => return,
+            },
+        };
+        if let Ok(contents) = fs::read_to_string(&path) {
+            self.entries.extend(contents.lines().filter(|line| !line.trim().is_empty()).map(String::from));
+            truncate(&mut self.entries, max_size());
+        }
+        self.path = Some(path);
+    }
+
+    /// Records `line` unless it's blank or `HISTCONTROL` says to leave it
+    /// out: `ignoredups`, the default when it's unset, skips a repeat of the
+    /// previous entry, `ignorespace` a line starting with a space, and
+    /// `ignoreboth` does both. Only the last `HISTSIZE` entries are kept.
+    /// Returns whether `line` was added.
+    pub fn add(&mut self, line: &str) -> bool {
+        let line = line.trim_end_matches('\n');
+        let control = env::var("HISTCONTROL").unwrap_or_else(|_| "ignoredups".to_string());
+        let ignores = |policy: &str| control.split(':').any(|value| value == policy || value == "ignoreboth");
+        if line.trim().is_empty()
+            || (ignores("ignorespace") && line.starts_with(' '))
+            || (ignores("ignoredups") && self.entries.last().is_some_and(|last| last == line))
+        {
+            return false;
+        }
+        self.entries.push(line.to_string());
+        truncate(&mut self.entries, max_size());
+        true
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Replaces each `!!` in `line` with the previous command and each `!n`
+    /// with entry `n`, numbered as the `history` builtin lists them. Single
+    /// quotes and backslashes keep a `!` literal. Returns `None` if `line`
+    /// has no events, or the first event with no entry as the error.
+    pub fn expand(&self, line: &str) -> Result<Option<String>, String> {
+        let mut expanded = String::new();
+        let mut changed = false;
+        let mut in_double = false;
+        let mut rest = line;
+
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+            match c {
+                '\\' => {
+                    expanded.push(c);
+                    if let Some(next) = rest.chars().next() {
+                        expanded.push(next);
+                        rest = &rest[next.len_utf8()..];
+                    }
+                }
+                '\'' if !in_double => {
+                    let end = rest.find('\'').map_or(rest.len(), |end| end + 1);
+                    expanded.push(c);
+                    expanded.push_str(&rest[..end]);
+                    rest = &rest[end..];
+                }
+                '"' => {
+                    in_double = !in_double;
+                    expanded.push(c);
+                }
+                '!' if rest.starts_with('!') => {
+                    let entry = self.entries.last().ok_or_else(|| "!!".to_string())?;
+                    expanded.push_str(entry);
+                    rest = &rest[1..];
+                    changed = true;
+                }
+                '!' if rest.starts_with(|c: char| c.is_ascii_digit()) => {
+                    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+                    let event = &rest[..digits];
+                    let entry = event
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| self.entries.get(i))
+                        .ok_or_else(|| format!("!{}", event))?;
+                    expanded.push_str(entry);
+                    rest = &rest[digits..];
+                    changed = true;
+                }
+                c => expanded.push(c),
+            }
+        }
+
+        Ok(changed.then_some(expanded))
+    }
+
+    /// Writes the history back to the file it was loaded from, if any,
+    /// keeping the last `HISTFILESIZE` entries. That defaults to `HISTSIZE`.
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let size = env::var("HISTFILESIZE").ok().and_then(|size| size.parse().ok()).unwrap_or_else(max_size);
+        let start = self.entries.len().saturating_sub(size);
+        let mut contents = self.entries[start..].join("\n");
+        if !contents.is_empty() {
+            contents.push('\n');
+        }
+        if let Err(err) = fs::write(path, contents) {
+            eprintln!("mini-shell: {}: {}", path.display(), err);
+        }
+    }
+}
+
+/// The number of entries kept in memory, from `HISTSIZE`.
+pub fn max_size() -> usize {
+    env::var("HISTSIZE").ok().and_then(|size| size.parse().ok()).unwrap_or(DEFAULT_SIZE)
+}
+
+/// Drops the oldest entries so that at most `size` are left.
+fn truncate(entries: &mut Vec<String>, size: usize) {
+    let excess = entries.len().saturating_sub(size);
+    entries.drain(..excess);
+}
@@ -0,0 +1,212 @@
+use nix::errno::Errno;
+use nix::sys::signal::Signal;
+use nix::unistd::isatty;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{Behavior, Cmd, CompletionType, Config, Editor, KeyEvent};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::os::unix::io::RawFd;
+use crate::complete::ShellCompleter;
+use crate::heredoc::take_heredocs;
+use crate::history;
+use crate::signals;
+use crate::tokenizer::{line_state, LineState};
+
+/// Somewhere the shell reads command lines from.
+pub trait LineSource {
+    /// Appends the next line to `buf`, first showing `prompt` if the source is
+    /// interactive. Returns 0 at end of input.
+    fn read_line(&mut self, prompt: &str, buf: &mut String) -> io::Result<usize>;
+
+    /// The descriptor the prompt is shown on, so that it's only colored
+    /// where that will be seen on a terminal.
+    fn prompt_fd(&self) -> RawFd {
+        2
+    }
+
+    /// Offers an entered command to the source's own history, if it keeps one.
+    fn add_history(&mut self, _line: &str) {}
+}
+
+/// Lines are cut off at 4 MiB unless `$MINI_SHELL_MAX_LINE` sets another
+/// limit, in bytes, so a stream with no newlines in it can't use up memory.
+const DEFAULT_MAX_LINE: usize = 4 << 20;
+
+fn max_line_len() -> usize {
+    env::var("MINI_SHELL_MAX_LINE").ok().and_then(|max| max.parse().ok()).unwrap_or(DEFAULT_MAX_LINE)
+}
+
+/// The error for a line over the limit. The rest of the line is read and
+/// thrown away first, so reading can carry on with the next one.
+fn line_too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "line too long")
+}
+
+impl<R: BufRead> LineSource for R {
+    fn read_line(&mut self, _prompt: &str, buf: &mut String) -> io::Result<usize> {
+        let max = max_line_len();
+        let mut line = Vec::new();
+        let mut too_long = false;
+        loop {
+            let available = self.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let (len, done) = match available.iter().position(|&b| b == b'\n') {
+                Some(i) => (i + 1, true),
+                None => (available.len(), false),
+            };
+            // The newline doesn't count towards the limit.
+            too_long |= line.len() + len - usize::from(done) > max;
+            if too_long {
+                line = Vec::new();
+            } else {
+                line.extend_from_slice(&available[..len]);
+            }
+            self.consume(len);
+            if done {
+                break;
+            }
+        }
+        if too_long {
+            return Err(line_too_long());
+        }
+        let text = String::from_utf8(line)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8"))?;
+        buf.push_str(&text);
+        Ok(text.len())
+    }
+}
+
+/// Interactive input on stdin. Unlike `BufRead::read_line`, which retries
+/// whenever a read is interrupted, this reports `Interrupted` for Ctrl+C so
+/// that the prompt can discard the line.
+pub struct Terminal;
+
+impl LineSource for Terminal {
+    fn read_line(&mut self, prompt: &str, buf: &mut String) -> io::Result<usize> {
+        // Prompts go to stderr, keeping stdout for what commands print, and
+        // only to a person typing.
+        if isatty(0).unwrap_or(false) {
+            eprint!("{}", prompt);
+            io::stderr().flush()?;
+        }
+
+        let max = max_line_len();
+        let mut line = Vec::new();
+        let mut too_long = false;
+        let mut byte = [0u8; 1];
+        loop {
+            match nix::unistd::read(0, &mut byte) {
+                Ok(0) => break,
+                Ok(_) => {
+                    if byte[0] == b'\n' {
+                        line.push(byte[0]);
+                        break;
+                    }
+                    too_long |= line.len() >= max;
+                    if too_long {
+                        line = Vec::new();
+                    } else {
+                        line.push(byte[0]);
+                    }
+                }
+                // Only Ctrl+C abandons the line; other signals just interrupt
+                // the read, which picks up where it left off.
+                Err(Errno::EINTR) if signals::interrupt_pending() => return Err(io::ErrorKind::Interrupted.into()),
+                Err(Errno::EINTR) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        if too_long {
+            return Err(line_too_long());
+        }
+        buf.push_str(&String::from_utf8_lossy(&line));
+        Ok(line.len())
+    }
+}
+
+/// Interactive input on a terminal, with cursor movement and history recall.
+pub struct LineEditor {
+    editor: Editor<ShellCompleter, DefaultHistory>,
+}
+
+impl LineEditor {
+    pub fn new() -> io::Result<Self> {
+        let config = Config::builder()
+            // List every candidate on a second Tab, like bash, rather than cycling.
+            .completion_type(CompletionType::List)
+            // Edit on the terminal itself, so the prompt and echoed input
+            // stay out of stdout when it's redirected.
+            .behavior(Behavior::PreferTerm)
+            // Which lines are kept is up to the shell's own history.
+            .history_ignore_dups(false)
+            .and_then(|config| config.max_history_size(history::max_size()))
+            .map_err(io::Error::other)?
+            .build();
+        let mut editor = Editor::with_config(config).map_err(io::Error::other)?;
+        editor.set_helper(Some(ShellCompleter));
+        // Ctrl+Z at the prompt would otherwise stop the shell itself.
+        editor.bind_sequence(KeyEvent::ctrl('Z'), Cmd::Noop);
+        Ok(LineEditor { editor })
+    }
+}
+
+impl LineSource for LineEditor {
+    fn read_line(&mut self, prompt: &str, buf: &mut String) -> io::Result<usize> {
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                buf.push_str(&line);
+                buf.push('\n');
+                Ok(line.len() + 1)
+            }
+            Err(ReadlineError::Eof) => Ok(0),
+            // In raw mode Ctrl+C is read as a key rather than sent as SIGINT.
+            Err(ReadlineError::Interrupted) => {
+                signals::queue_trap(Signal::SIGINT);
+                Err(io::ErrorKind::Interrupted.into())
+            }
+            Err(ReadlineError::Io(err)) => Err(err),
+            Err(err) => Err(io::Error::other(err)),
+        }
+    }
+
+    fn add_history(&mut self, line: &str) {
+        let _ = self.editor.add_history_entry(line.trim_end_matches('\n'));
+    }
+
+    /// The editor draws on the terminal itself, which is the one on stdin.
+    fn prompt_fd(&self) -> RawFd {
+        0
+    }
+}
+
+/// Reads one command, following lines that end in a backslash or leave a
+/// quote open, and the bodies of any here-documents it uses. Returns `None`
+/// at end of input. Running out of input partway through a command returns
+/// what there is, for the parser to report what was left open.
+pub fn read_command(source: &mut dyn LineSource, prompt: &str) -> io::Result<Option<String>> {
+    let mut input = String::new();
+    if source.read_line(prompt, &mut input)? == 0 {
+        return Ok(None);
+    }
+
+    loop {
+        let commands = take_heredocs(&input, 0);
+        match line_state(&commands.text) {
+            LineState::Complete if commands.complete => return Ok(Some(input)),
+            // Keep reading here-document bodies up to their delimiters.
+            LineState::Complete => {}
+            LineState::Continued => {
+                let len = input.trim_end_matches('\n').len() - 1;
+                input.truncate(len);
+            }
+            LineState::OpenQuote => {}
+        }
+
+        if source.read_line("> ", &mut input)? == 0 {
+            return Ok(Some(input));
+        }
+    }
+}
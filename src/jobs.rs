@@ -0,0 +1,374 @@
+use nix::errno::Errno;
+use nix::sys::signal::{kill, killpg, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::{fork, getpgrp, setpgid, tcsetpgrp, ForkResult, Pid};
+use std::fmt;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::color;
+use crate::signals;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum JobState {
+    Running,
+    Stopped,
+    Done(i32),
+}
+
+impl fmt::Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobState::Running => write!(f, "Running"),
+            JobState::Stopped => write!(f, "Stopped"),
+            JobState::Done(0) => write!(f, "Done"),
+            JobState::Done(code) => write!(f, "Exit {}", code),
+        }
+    }
+}
+
+pub struct Job {
+    pub id: usize,
+    pub pgid: Pid,
+    /// Processes in the job that haven't finished yet.
+    pub pids: Vec<Pid>,
+    pub command: String,
+    pub state: JobState,
+}
+
+#[derive(Default)]
+pub struct Jobs {
+    jobs: Vec<Job>,
+    /// The shell's own process group, set once job control is enabled.
+    shell_pgid: Option<Pid>,
+}
+
+impl Jobs {
+    /// Puts the shell in its own process group in the foreground of the
+    /// terminal, so children can be moved in and out of the foreground.
+    pub fn enable_control(&mut self) {
+        let pid = Pid::this();
+        let _ = setpgid(pid, pid);
+        let _ = tcsetpgrp(0, pid);
+        self.shell_pgid = Some(getpgrp());
+    }
+
+    pub fn control_enabled(&self) -> bool {
+        self.shell_pgid.is_some()
+    }
+
+    /// Called in a forked child to join the job's process group, `None`
+    /// meaning the child leads a new one. Foreground jobs also take the
+    /// terminal so they can read from it.
+    pub fn enter_group(&self, pgid: Option<Pid>, foreground: bool) {
+        if !self.control_enabled() {
+            return;
+        }
+        let pgid = pgid.unwrap_or_else(Pid::this);
+        let _ = setpgid(Pid::from_raw(0), pgid);
+        if foreground {
+            let _ = tcsetpgrp(0, pgid);
+        }
+    }
+
+    /// Puts the shell back in the foreground once a job is done with the
+    /// terminal.
+    pub fn reclaim_terminal(&self) {
+        if let Some(shell_pgid) = self.shell_pgid {
+            let _ = tcsetpgrp(0, shell_pgid);
+        }
+    }
+
+    /// The parent's half of `enter_group`; both sides call `setpgid` so
+    /// neither has to wait for the other.
+    pub fn assign_group(&self, child: Pid, pgid: Option<Pid>) {
+        if self.control_enabled() {
+            let _ = setpgid(child, pgid.unwrap_or(child));
+        }
+    }
+
+    /// Records a job and returns its job number.
+    pub fn add(&mut self, pgid: Pid, pids: Vec<Pid>, command: &str, state: JobState) -> usize {
+        let id = self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        self.jobs.push(Job { id, pgid, pids, command: command.to_string(), state });
+        id
+    }
+
+    /// Looks up a job from a `%n` or `n` spec, or the most recent job when
+    /// `spec` is `None`.
+    pub fn find(&self, spec: Option<&str>) -> Option<&Job> {
+        match spec {
+            None => self.jobs.last(),
+            Some(spec) => {
+                let id: usize = spec.strip_prefix('%').unwrap_or(spec).parse().ok()?;
+                self.jobs.iter().find(|job| job.id == id)
+            }
+        }
+    }
+
+    /// Sends `signal` to every process in job `id`. Under job control the
+    /// job has a process group of its own; otherwise it shares the shell's,
+    /// so its processes are signalled one by one.
+    pub fn signal(&self, id: usize, signal: Signal) -> nix::Result<()> {
+        let Some(job) = self.jobs.iter().find(|job| job.id == id) else {
+            return Err(Errno::ESRCH);
+        };
+        if self.control_enabled() {
+            return killpg(job.pgid, signal);
+        }
+        for &pid in &job.pids {
+            kill(pid, signal)?;
+        }
+        Ok(())
+    }
+
+    /// The job that process `pid` belongs to.
+    pub fn job_of(&self, pid: Pid) -> Option<usize> {
+        self.jobs.iter().find(|job| job.pgid == pid || job.pids.contains(&pid)).map(|job| job.id)
+    }
+
+    /// The numbers of the jobs that are still running.
+    pub fn running(&self) -> Vec<usize> {
+        self.jobs.iter().filter(|job| job.state == JobState::Running).map(|job| job.id).collect()
+    }
+
+    /// Blocks until every process in job `id` has finished, then drops the
+    /// job and returns the exit status of its last process. Ctrl+C gives up
+    /// on the wait and returns 130. Returns `None` if there's no such job.
+    pub fn wait(&mut self, id: usize) -> Option<i32> {
+        let job = self.jobs.iter_mut().find(|job| job.id == id)?;
+        let mut status = match job.state {
+            JobState::Done(code) => code,
+            _ => 0,
+        };
+        while let Some(&pid) = job.pids.first() {
+            match waitpid(pid, None) {
+                Ok(wait_status @ (WaitStatus::Exited(..) | WaitStatus::Signaled(..))) => {
+                    status = exit_code(wait_status);
+                }
+                Ok(_) => continue,
+                Err(Errno::EINTR) if signals::interrupt_pending() => {
+                    // Move past the `^C` the terminal echoed.
+                    eprintln!();
+                    return Some(128 + Signal::SIGINT as i32);
+                }
+                Err(Errno::EINTR) => continue,
+                Err(_) => status = 1,
+            }
+            job.pids.remove(0);
+        }
+        self.remove(id);
+        Some(status)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Job> {
+        let index = self.jobs.iter().position(|job| job.id == id)?;
+        Some(self.jobs.remove(index))
+    }
+
+    pub fn set_state(&mut self, id: usize, state: JobState) {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+            job.state = state;
+        }
+    }
+
+    /// Polls children without blocking and records any state changes.
+    fn update(&mut self) {
+        let flags = WaitPidFlag::WNOHANG | WaitPidFlag::WUNTRACED | WaitPidFlag::WCONTINUED;
+        loop {
+            let (pid, state) = match waitpid(Pid::from_raw(-1), Some(flags)) {
+                Ok(WaitStatus::Exited(pid, code)) => (pid, JobState::Done(code)),
+                Ok(WaitStatus::Signaled(pid, signal, _)) => (pid, JobState::Done(128 + signal as i32)),
+                Ok(WaitStatus::Stopped(pid, _)) => (pid, JobState::Stopped),
+                Ok(WaitStatus::Continued(pid)) => (pid, JobState::Running),
+                Ok(WaitStatus::StillAlive) | Err(_) => break,
+                Ok(_) => continue,
+            };
+            let Some(job) = self.jobs.iter_mut().find(|job| job.pids.contains(&pid)) else {
+                continue;
+            };
+            if let JobState::Done(_) = state {
+                job.pids.retain(|&p| p != pid);
+                if !job.pids.is_empty() {
+                    continue;
+                }
+            }
+            job.state = state;
+        }
+    }
+
+    /// Collects any background children that have exited so they don't linger
+    /// as zombies, reporting each finished job.
+    pub fn reap(&mut self) {
+        self.update();
+        self.jobs.retain(|job| {
+            if let JobState::Done(_) = job.state {
+                let _ = writeln!(io::stdout(), "[{}]+  {:<10}{}", job.id, job.state.to_string(), job.command);
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Describes every job, one line each, dropping the ones that have
+    /// finished.
+    pub fn list(&mut self) -> Vec<String> {
+        self.update();
+        let lines = self
+            .jobs
+            .iter()
+            .map(|job| format!("[{}]  {:<10}{}", job.id, job.state.to_string(), job.command))
+            .collect();
+        self.jobs.retain(|job| !matches!(job.state, JobState::Done(_)));
+        lines
+    }
+
+    /// Waits for a foreground job to finish or stop, handing it the terminal
+    /// in the meantime. A job stopped with Ctrl+Z is added to the table. Returns
+    /// the exit status of the last process in the job.
+    pub fn wait_foreground(&mut self, pgid: Pid, pids: Vec<Pid>, command: &str) -> i32 {
+        if self.control_enabled() {
+            let _ = tcsetpgrp(0, pgid);
+        }
+
+        let mut status = 0;
+        let mut remaining = pids.clone();
+        let mut stopped = false;
+        let mut interrupted = false;
+        let mut killed = None;
+        for &pid in &pids {
+            match wait_for(pid) {
+                WaitStatus::Stopped(..) => {
+                    stopped = true;
+                    break;
+                }
+                wait_status => {
+                    match wait_status {
+                        WaitStatus::Signaled(_, Signal::SIGINT, _) => interrupted = true,
+                        // A reader going away is how pipelines normally end.
+                        WaitStatus::Signaled(_, Signal::SIGPIPE, _) => {}
+                        WaitStatus::Signaled(_, signal, _) => killed = Some(signal),
+                        _ => {}
+                    }
+                    status = exit_code(wait_status);
+                    remaining.retain(|&p| p != pid);
+                }
+            }
+        }
+
+        // Move past the `^C` the terminal echoed.
+        if interrupted {
+            eprintln!();
+            // Under job control the SIGINT only went to the job, but a trap
+            // on it still runs, as in sh.
+            if self.control_enabled() {
+                signals::queue_trap(Signal::SIGINT);
+            }
+        }
+        if let Some(signal) = killed {
+            eprintln!("Killed by signal {}", signal as i32);
+        }
+
+        self.reclaim_terminal();
+
+        if stopped {
+            let id = self.add(pgid, remaining, command, JobState::Stopped);
+            let _ = writeln!(io::stdout(), "\n[{}]+  Stopped {}", id, command);
+            return 128 + Signal::SIGTSTP as i32;
+        }
+        status
+    }
+
+    /// Resumes a stopped job, in the foreground if `foreground` is set.
+    pub fn resume(&mut self, id: usize, foreground: bool) -> i32 {
+        let Some(job) = self.jobs.iter().find(|job| job.id == id) else {
+            return 1;
+        };
+        // A foreground job needs the terminal before it wakes up and reads it.
+        if foreground && self.control_enabled() {
+            let _ = tcsetpgrp(0, job.pgid);
+        }
+        let _ = killpg(job.pgid, Signal::SIGCONT);
+        if !foreground {
+            let _ = writeln!(io::stdout(), "[{}]+ {} &", job.id, job.command);
+            self.set_state(id, JobState::Running);
+            return 0;
+        }
+
+        // The job runs whether or not its name could be shown.
+        let _ = writeln!(io::stdout(), "{}", job.command);
+        let Some(job) = self.remove(id) else {
+            return 1;
+        };
+        self.wait_foreground(job.pgid, job.pids, &job.command)
+    }
+}
+
+/// How many times a fork that failed for want of resources is tried again.
+const FORK_RETRIES: u32 = 3;
+
+/// Forks like `fork`, but tries again after a pause when the system is out
+/// of processes or memory for the moment, waiting longer each time.
+///
+/// # Safety
+///
+/// The same as for `fork`.
+pub unsafe fn fork_retrying() -> nix::Result<ForkResult> {
+    let mut delay = Duration::from_millis(100);
+    for _ in 0..FORK_RETRIES {
+        match unsafe { fork() } {
+            Err(Errno::EAGAIN | Errno::ENOMEM) => {
+                thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    unsafe { fork() }
+}
+
+/// Reports that `command` couldn't be started because the fork failed.
+pub fn fork_failed(command: &str, err: Errno) {
+    eprintln!("{}", color::error(&format!("mini-shell: {}: fork failed: {}", command, err.desc())));
+}
+
+/// Waits for `child` to finish or stop.
+pub fn wait_for(child: Pid) -> WaitStatus {
+    loop {
+        match waitpid(child, Some(WaitPidFlag::WUNTRACED)) {
+            Ok(status @ (WaitStatus::Exited(..) | WaitStatus::Signaled(..) | WaitStatus::Stopped(..))) => {
+                return status;
+            }
+            Ok(_) => continue,
+            // A signal aimed at the shell interrupted the wait; keep waiting.
+            Err(Errno::EINTR) => continue,
+            Err(_) => return WaitStatus::Exited(child, 1),
+        }
+    }
+}
+
+/// Like `wait_for`, but gives up and returns `None` if `child` is still
+/// running at `deadline`.
+pub fn wait_until(child: Pid, deadline: Instant) -> Option<WaitStatus> {
+    loop {
+        match waitpid(child, Some(WaitPidFlag::WNOHANG)) {
+            Ok(WaitStatus::StillAlive) => {}
+            Ok(status @ (WaitStatus::Exited(..) | WaitStatus::Signaled(..))) => return Some(status),
+            Ok(_) | Err(Errno::EINTR) => {}
+            Err(_) => return Some(WaitStatus::Exited(child, 1)),
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Converts how a process finished into a shell exit status.
+pub fn exit_code(status: WaitStatus) -> i32 {
+    match status {
+        WaitStatus::Exited(_, code) => code,
+        WaitStatus::Signaled(_, signal, _) => 128 + signal as i32,
+        _ => 1,
+    }
+}
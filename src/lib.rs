@@ -0,0 +1,37 @@
+//! The engine behind mini-shell, for running its commands from another
+//! program. A [`Shell`] holds a session's state: variables live in the
+//! process environment, and the working directory is the process's own.
+//! `exit` doesn't end the process: [`Shell::exit_requested`] says it was
+//! run, and [`Shell::finish`] gives the status to end with.
+
+mod alias;
+mod arith;
+mod brace;
+mod builtins;
+mod color;
+mod complete;
+mod condition;
+pub mod error;
+mod executor;
+mod expand;
+mod glob;
+mod heredoc;
+mod history;
+mod input;
+mod jobs;
+mod parser;
+mod path;
+mod pipeline;
+mod prompt;
+mod redirect;
+mod shell;
+mod signals;
+mod substitute;
+mod tokenizer;
+
+pub use shell::{Shell, ShellOptions};
+
+/// Held by the unit tests that change what the whole process shares: the
+/// environment, the working directory or descriptor 1.
+#[cfg(test)]
+static PROCESS_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
@@ -1,66 +1,111 @@
-mod executor;
-
-use std::io::{self, Write};
+use mini_shell::error::describe;
+use mini_shell::Shell;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::process::exit;
-use nix::sys::wait::waitpid;
-use nix::fcntl::{open, OFlag};
-use nix::sys::stat::Mode;
-use nix::unistd::dup2;
-use executor::run_execvp;
-
-fn main() {
-    loop {
-        let dir = nix::unistd::getcwd().unwrap();
-        print!("mini-shell({})> ", dir.display());
 
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
+/// Command-line options, which come before any script name.
+#[derive(Default)]
+struct Options {
+    /// The command given with `-c`, run in place of a script.
+    command: Option<String>,
+    norc: bool,
+    rcfile: Option<String>,
+}
 
-        if io::stdin().read_line(&mut input).is_err() {
-            eprintln!("Failed to read line");
-            continue;
+/// Reads the options at the start of `args`, returning them along with the
+/// index of the first argument that isn't one.
+fn parse_options(args: &[String]) -> (Options, usize) {
+    let mut options = Options::default();
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        match arg.as_str() {
+            "-c" | "--command" => {
+                i += 1;
+                let Some(command) = args.get(i) else {
+                    eprintln!("mini-shell: {}: option requires an argument", arg);
+                    exit(2);
+                };
+                options.command = Some(command.clone());
+            }
+            "--norc" => options.norc = true,
+            "--rcfile" => {
+                i += 1;
+                let Some(path) = args.get(i) else {
+                    eprintln!("mini-shell: --rcfile: option requires an argument");
+                    exit(2);
+                };
+                options.rcfile = Some(path.clone());
+            }
+            _ => break,
         }
+        i += 1;
+    }
+    (options, i)
+}
 
-        let command: &str = input.trim();
-        if command == "exit" {
-            exit(0);
+/// The rc file to source, if there is one to run. The default
+/// `~/.mini_shellrc` is skipped quietly when it doesn't exist; only scripts
+/// that ask for an rc file with `--rcfile` get one.
+fn rc_file(options: &Options, interactive: bool) -> Option<String> {
+    if options.norc {
+        return None;
+    }
+    match &options.rcfile {
+        Some(path) => Some(path.clone()),
+        None if interactive => {
+            let home = env::var("HOME").ok()?;
+            Path::new(&home).join(".mini_shellrc").exists().then(|| format!("{}/.mini_shellrc", home))
         }
+        None => None,
+    }
+}
 
-        let tokens: Vec<&str>= command.split_ascii_whitespace().collect();
-
-        if tokens.is_empty() {
-            continue;
+/// Runs the rc file for a script or `-c` command, if it gets one. Returns
+/// false if it asked for the shell to exit.
+fn source_rc_file(options: &Options, shell: &mut Shell) -> bool {
+    match rc_file(options, false) {
+        Some(path) => {
+            shell.source(&path);
+            !shell.exit_requested()
         }
+        None => true,
+    }
+}
 
-        if tokens[0] == "cd" {
-            let target = tokens.get(1).unwrap_or(&"/").to_string();
-            if let Err(err) = nix::unistd::chdir(target.as_str()) {
-                eprintln!("cd :{}", err);
-            }
-            continue;
-        }
+fn main() {
+    let mut shell = Shell::new();
+    let args: Vec<String> = env::args().collect();
+    let (options, first) = parse_options(&args);
 
-        match unsafe { nix::unistd::fork() } {
-            Ok(nix::unistd::ForkResult::Child) => {
-                if let Some((cmd, filename)) = command.split_once('>') {
-                    let fd = open(filename.trim(), OFlag::O_CREAT | OFlag::O_WRONLY | OFlag::O_TRUNC, Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
-                    let _ = dup2(fd, 1);
-                    run_execvp(cmd);
-                } else if let Some((cmd, filename)) = command.split_once('<'){
-                  let fd = open(filename.trim(), OFlag::O_RDONLY, Mode::empty()).unwrap();
-                    let _ = dup2(fd, 0);
-                    run_execvp(cmd);
-                } else {
-                    run_execvp(command);
-                }   
-            }
-            Ok(nix::unistd::ForkResult::Parent { child }) => {
-               let _  = waitpid(child, None);
-            }
-            Err(err) => {
-                eprintln!("Fork failed: {}", err);
-            }
+    if let Some(command) = &options.command {
+        // As with `sh -c`, the arguments after the command start at `$0`.
+        shell.positional = match &args[first..] {
+            [] => vec!["mini-shell".to_string()],
+            rest => rest.to_vec(),
+        };
+        if source_rc_file(&options, &mut shell) {
+            shell.run_script(&mut command.as_bytes(), "-c");
+        }
+        exit(shell.finish());
+    }
+    if let Some(path) = args.get(first) {
+        // `$0` is the script and `$1` onwards the arguments after it.
+        shell.positional = args[first..].to_vec();
+        if source_rc_file(&options, &mut shell) {
+            let file = match File::open(path) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("mini-shell: {}: {}", path, describe(&err));
+                    exit(127);
+                }
+            };
+            shell.run_script(&mut BufReader::new(file), path);
         }
+        exit(shell.finish());
     }
+    shell.positional = vec!["mini-shell".to_string()];
+    exit(shell.run_interactive(rc_file(&options, true).as_deref()));
 }
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::alias::expand_aliases;
+use crate::error::ShellError;
+use crate::redirect::{parse_redirections, Redirection};
+use crate::tokenizer::{find_operators, split_unquoted, tokenize, Word};
+
+/// A simple command as written: its words and redirections, which are only
+/// expanded when it runs.
+pub struct SimpleCommand {
+    pub words: Vec<Word>,
+    pub redirections: Vec<Redirection>,
+    /// The command as typed, for job listings.
+    pub text: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Connector {
+    And,
+    Or,
+}
+
+/// A parsed command line.
+pub enum Node {
+    Command(SimpleCommand),
+    /// Two or more commands joined by `|`.
+    Pipeline(Vec<SimpleCommand>),
+    /// Runs the right side only if the left side succeeded (`&&`) or
+    /// failed (`||`).
+    AndOr(Box<Node>, Connector, Box<Node>),
+    /// Commands separated by `;` or newlines, run one after another.
+    Sequence(Vec<Node>),
+    /// A command followed by `&`, run without waiting for it.
+    Background(Box<Node>),
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Node::Command(command) => write!(f, "{}", command.text),
+            Node::Pipeline(stages) => {
+                let texts: Vec<&str> = stages.iter().map(|stage| stage.text.as_str()).collect();
+                write!(f, "{}", texts.join(" | "))
+            }
+            Node::AndOr(left, Connector::And, right) => write!(f, "{} && {}", left, right),
+            Node::AndOr(left, Connector::Or, right) => write!(f, "{} || {}", left, right),
+            Node::Sequence(nodes) => {
+                let texts: Vec<String> = nodes.iter().map(Node::to_string).collect();
+                write!(f, "{}", texts.join("; "))
+            }
+            Node::Background(node) => write!(f, "{} &", node),
+        }
+    }
+}
+
+/// Parses a line whose here-document bodies have already been taken out.
+/// Aliases are expanded before anything else, so one defined on a line
+/// applies from the next line on, as in sh.
+pub fn parse(line: &str, aliases: &HashMap<String, String>) -> Result<Node, ShellError> {
+    let line = &expand_aliases(line, aliases);
+    // Parentheses only have a meaning as part of `$(...)` or `$((...))`.
+    if let Some(&(_, paren)) = find_operators(line, &["(", ")"]).first() {
+        return Err(ShellError::UnexpectedToken(paren.to_string()));
+    }
+
+    let mut nodes = Vec::new();
+    let mut start = 0;
+    // A newline ends a command just like `;` once input spans several lines,
+    // and `&` does too, putting the whole `&&`/`||` list before it in the
+    // background. The `&&` operators are found only to be passed over.
+    let separators = find_operators(line, &["&&", ";", "\n", "&"]);
+    for (pos, op) in separators.into_iter().filter(|&(_, op)| op != "&&").chain([(line.len(), "")]) {
+        let part = line[start..pos].trim();
+        start = pos + op.len();
+        if part.is_empty() {
+            if op == "&" {
+                return Err(ShellError::UnexpectedToken(op.to_string()));
+            }
+            continue;
+        }
+        let node = parse_and_or(part)?;
+        nodes.push(if op == "&" { Node::Background(Box::new(node)) } else { node });
+    }
+    Ok(Node::Sequence(nodes))
+}
+
+fn parse_and_or(text: &str) -> Result<Node, ShellError> {
+    let operators = find_operators(text, &["&&", "||"]);
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for &(pos, op) in &operators {
+        parts.push(&text[start..pos]);
+        start = pos + op.len();
+    }
+    parts.push(&text[start..]);
+
+    // Each operator needs a command on both sides.
+    if let Some(i) = parts.iter().position(|part| part.trim().is_empty()) {
+        let (_, op) = operators[i.min(operators.len() - 1)];
+        return Err(ShellError::UnexpectedToken(op.to_string()));
+    }
+
+    let mut node = parse_pipeline(parts[0])?;
+    for (&(_, op), part) in operators.iter().zip(&parts[1..]) {
+        let connector = if op == "&&" { Connector::And } else { Connector::Or };
+        node = Node::AndOr(Box::new(node), connector, Box::new(parse_pipeline(part)?));
+    }
+    Ok(node)
+}
+
+fn parse_pipeline(text: &str) -> Result<Node, ShellError> {
+    let text = text.trim();
+    let stages = split_unquoted(text, &["|"]);
+    if stages.iter().any(|stage| stage.trim().is_empty()) {
+        return Err(ShellError::UnexpectedToken("|".to_string()));
+    }
+    if let [command] = stages.as_slice() {
+        return Ok(Node::Command(parse_simple(command)?));
+    }
+    let commands = stages.iter().map(|stage| parse_simple(stage)).collect::<Result<_, _>>()?;
+    Ok(Node::Pipeline(commands))
+}
+
+fn parse_simple(text: &str) -> Result<SimpleCommand, ShellError> {
+    let (words, redirections) = parse_redirections(tokenize(text)?)?;
+    Ok(SimpleCommand { words, redirections, text: text.trim().to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Node {
+        parse(line, &HashMap::new()).unwrap()
+    }
+
+    /// The commands of a parsed line, which is always a sequence.
+    fn commands(line: &str) -> Vec<Node> {
+        match parse_line(line) {
+            Node::Sequence(nodes) => nodes,
+            _ => panic!("{:?} didn't parse to a sequence", line),
+        }
+    }
+
+    fn text(node: &Node) -> &str {
+        match node {
+            Node::Command(command) => &command.text,
+            _ => panic!("{} isn't a simple command", node),
+        }
+    }
+
+    fn unexpected_token(line: &str) -> String {
+        match parse(line, &HashMap::new()) {
+            Err(ShellError::UnexpectedToken(token)) => token,
+            Err(err) => panic!("{:?} failed with {}", line, err),
+            Ok(node) => panic!("{:?} parsed as {}", line, node),
+        }
+    }
+
+    #[test]
+    fn semicolons_and_newlines_separate_commands() {
+        let nodes = commands("echo a; echo b\necho c;");
+        let texts: Vec<&str> = nodes.iter().map(text).collect();
+        assert_eq!(texts, ["echo a", "echo b", "echo c"]);
+        assert!(commands("  ").is_empty());
+    }
+
+    #[test]
+    fn and_or_lists_group_from_the_left() {
+        let nodes = commands("a && b || c");
+        let [Node::AndOr(left, Connector::Or, right)] = nodes.as_slice() else {
+            panic!("not an `||` list");
+        };
+        assert!(matches!(left.as_ref(), Node::AndOr(_, Connector::And, _)));
+        assert_eq!(left.to_string(), "a && b");
+        assert_eq!(text(right), "c");
+    }
+
+    #[test]
+    fn pipes_join_stages() {
+        let nodes = commands("ls -l | grep x | wc -l");
+        let [Node::Pipeline(stages)] = nodes.as_slice() else {
+            panic!("not a pipeline");
+        };
+        let texts: Vec<&str> = stages.iter().map(|stage| stage.text.as_str()).collect();
+        assert_eq!(texts, ["ls -l", "grep x", "wc -l"]);
+        // `||` is a list, and the `|` of `>|` a redirection.
+        assert!(matches!(commands("a || b").as_slice(), [Node::AndOr(..)]));
+        assert!(matches!(commands("echo x >| out").as_slice(), [Node::Command(_)]));
+    }
+
+    #[test]
+    fn ampersand_backgrounds_the_list_before_it() {
+        let nodes = commands("sleep 1 & echo now");
+        let [Node::Background(job), Node::Command(_)] = nodes.as_slice() else {
+            panic!("not a background job then a command");
+        };
+        assert_eq!(text(job), "sleep 1");
+
+        let nodes = commands("a && b &");
+        assert!(matches!(nodes.as_slice(), [Node::Background(job)] if matches!(job.as_ref(), Node::AndOr(..))));
+        // Only a lone `&` separates; these are redirections.
+        assert_eq!(commands("cmd 2>&1 &> all <&3").len(), 1);
+    }
+
+    #[test]
+    fn operators_need_commands_around_them() {
+        assert_eq!(unexpected_token("& echo"), "&");
+        assert_eq!(unexpected_token("a; & b"), "&");
+        assert_eq!(unexpected_token("a && && b"), "&&");
+        assert_eq!(unexpected_token("a &&"), "&&");
+        assert_eq!(unexpected_token("|| a"), "||");
+        assert_eq!(unexpected_token("a | | b"), "|");
+        assert_eq!(unexpected_token("a |"), "|");
+        assert_eq!(unexpected_token("(a)"), "(");
+    }
+
+    #[test]
+    fn other_syntax_errors() {
+        assert!(matches!(parse("echo 'unclosed", &HashMap::new()), Err(ShellError::Syntax(_))));
+        assert!(matches!(parse("cat <", &HashMap::new()), Err(ShellError::Syntax(_))));
+    }
+}
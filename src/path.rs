@@ -0,0 +1,68 @@
+use nix::sys::stat::{stat, SFlag};
+use nix::unistd::getcwd;
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves a command name the way `execvp` does: a name containing `/` is
+/// used as it is, anything else is looked for in each `$PATH` directory in
+/// turn, with an empty entry meaning the current directory. Returns the first
+/// match that is an executable file.
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        return is_executable(Path::new(name)).then(|| PathBuf::from(name));
+    }
+    let path = env::var("PATH").ok()?;
+    path.split(':')
+        .map(|dir| Path::new(if dir.is_empty() { "." } else { dir }).join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Whether `path` is a regular file with an execute bit set.
+pub fn is_executable(path: &Path) -> bool {
+    stat(path).is_ok_and(|st| {
+        st.st_mode & SFlag::S_IFMT.bits() == SFlag::S_IFREG.bits() && st.st_mode & 0o111 != 0
+    })
+}
+
+/// The current directory as `$PWD` has it, keeping any symlinks it was
+/// reached through, as long as that still names the current directory.
+/// Otherwise the physical path.
+pub fn logical_cwd() -> nix::Result<PathBuf> {
+    if let Some(pwd) = env::var_os("PWD").map(PathBuf::from)
+        && pwd.is_absolute()
+        && let (Ok(named), Ok(current)) = (stat(&pwd), stat("."))
+        && (named.st_dev, named.st_ino) == (current.st_dev, current.st_ino)
+    {
+        return Ok(pwd);
+    }
+    getcwd()
+}
+
+/// Resolves `.` and `..` in `path` textually, without following symlinks,
+/// so that `..` after a symlink leads back to where the link is.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            component => normal.push(component),
+        }
+    }
+    normal
+}
+
+/// `path` for display, with a leading `$HOME` shown as `~`.
+pub fn abbreviate_home(path: &Path) -> String {
+    if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty())
+        && let Ok(rest) = path.strip_prefix(&home)
+    {
+        if rest.as_os_str().is_empty() {
+            return "~".to_string();
+        }
+        return format!("~/{}", rest.display());
+    }
+    path.display().to_string()
+}
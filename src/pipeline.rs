@@ -0,0 +1,89 @@
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::{close, dup2, pipe, ForkResult};
+use std::os::unix::io::RawFd;
+use crate::executor::run_stage;
+use crate::jobs::{fork_failed, fork_retrying, wait_for, JobState};
+use crate::parser::SimpleCommand;
+use crate::shell::Shell;
+use crate::signals;
+
+/// Runs the stages of a pipeline as one job. In the foreground, waits for it
+/// and returns the status of the last stage.
+pub fn run_pipeline(stages: &[SimpleCommand], text: &str, background: bool, shell: &mut Shell) -> i32 {
+    // One pipe links each pair of adjacent stages.
+    let mut fds: Vec<(RawFd, RawFd)> = Vec::new();
+    for _ in 1..stages.len() {
+        match pipe() {
+            Ok(fd) => fds.push(fd),
+            Err(err) => {
+                eprintln!("Pipe failed: {}", err);
+                close_all(&fds);
+                return 1;
+            }
+        }
+    }
+
+    let mut children = Vec::new();
+    let mut pgid = None;
+    let mut failed = false;
+    for (i, stage) in stages.iter().enumerate() {
+        match unsafe { fork_retrying() } {
+            Ok(ForkResult::Child) => {
+                shell.jobs.enter_group(pgid, !background);
+                signals::reset_child_signals();
+                if i > 0 {
+                    let _ = dup2(fds[i - 1].0, 0);
+                }
+                if i < stages.len() - 1 {
+                    let _ = dup2(fds[i].1, 1);
+                }
+                // Every stage must drop all pipe ends, otherwise readers never see EOF.
+                close_all(&fds);
+                run_stage(stage, shell);
+            }
+            Ok(ForkResult::Parent { child }) => {
+                shell.jobs.assign_group(child, pgid);
+                pgid.get_or_insert(child);
+                children.push(child);
+            }
+            Err(err) => {
+                fork_failed(&stage.text, err);
+                failed = true;
+                break;
+            }
+        }
+    }
+
+    close_all(&fds);
+    let Some(pgid) = pgid else {
+        return 1;
+    };
+    // The stages that did start can't do their part without the rest.
+    if failed {
+        for &child in &children {
+            let _ = kill(child, Signal::SIGTERM);
+        }
+        for child in children {
+            wait_for(child);
+        }
+        shell.jobs.reclaim_terminal();
+        return 1;
+    }
+    if background {
+        let Some(&last) = children.last() else {
+            return 1;
+        };
+        let job = shell.jobs.add(pgid, children, text, JobState::Running);
+        shell.last_background = Some(last);
+        println!("[{}] {}", job, last);
+        return 0;
+    }
+    shell.jobs.wait_foreground(pgid, children, text)
+}
+
+fn close_all(fds: &[(RawFd, RawFd)]) {
+    for &(read_end, write_end) in fds {
+        let _ = close(read_end);
+        let _ = close(write_end);
+    }
+}
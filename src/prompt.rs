@@ -0,0 +1,122 @@
+use nix::unistd::{gethostname, getuid, User};
+use std::env;
+use std::os::unix::io::RawFd;
+use crate::color::{paint, prompt_color};
+use crate::path::{abbreviate_home, logical_cwd};
+
+/// Builds the prompt from `$PS1`, or the usual `mini-shell(<dir>)> ` when it
+/// isn't set. The usual prompt shows a failed last command's status, as in
+/// `mini-shell(<dir>)[127]> `, unless `MINISHELL_SHOW_STATUS` is `0`. `fd`
+/// is where the prompt will be written.
+pub fn prompt(last_status: i32, fd: RawFd) -> String {
+    match env::var("PS1") {
+        Ok(ps1) => render_prompt(&ps1, fd),
+        Err(_) if last_status != 0 && env::var("MINISHELL_SHOW_STATUS").as_deref() != Ok("0") => {
+            render_prompt(&format!("mini-shell(\\w)[{}]> ", last_status), fd)
+        }
+        Err(_) => render_prompt("mini-shell(\\w)> ", fd),
+    }
+}
+
+/// Replaces the escapes in a `PS1` string: `\w` is the working directory, with
+/// `$HOME` shown as `~`, in color if `fd` is a terminal and `?` if it can't be
+/// found, `\u` the user name, `\h` the host name up to the first dot, `\$` a
+/// `$` and `\\` a backslash. Any other backslash is left as it is.
+pub fn render_prompt(ps1: &str, fd: RawFd) -> String {
+    let mut out = String::new();
+    let mut chars = ps1.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.clone().next() {
+            Some('w') => {
+                // The directory can be removed from under the shell.
+                let dir = logical_cwd().map_or_else(|_| "?".to_string(), |dir| abbreviate_home(&dir));
+                out.push_str(&paint(&dir, &prompt_color(), fd));
+            }
+            Some('u') => out.push_str(&user_name()),
+            Some('h') => {
+                let host = gethostname().map(|host| host.to_string_lossy().into_owned()).unwrap_or_default();
+                out.push_str(host.split('.').next().unwrap_or_default());
+            }
+            Some('$') => out.push('$'),
+            Some('\\') => out.push('\\'),
+            _ => {
+                out.push('\\');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    out
+}
+
+fn user_name() -> String {
+    match User::from_uid(getuid()) {
+        Ok(Some(user)) => user.name,
+        _ => env::var("USER").unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nix::unistd::getcwd;
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+
+    #[test]
+    fn working_directory() {
+        let _lock = crate::PROCESS_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let cwd = getcwd().unwrap();
+        // Changing the environment is only safe while no other test reads
+        // it, so everything that depends on it is checked here.
+        unsafe {
+            env::set_var("NO_COLOR", "1");
+            env::set_var("PWD", &cwd);
+            env::set_var("HOME", "/nonexistent");
+        }
+        assert_eq!(render_prompt("\\w> ", 2), format!("{}> ", cwd.display()));
+
+        unsafe { env::set_var("HOME", &cwd) };
+        assert_eq!(render_prompt("[\\w]", 2), "[~]");
+
+        let parent = cwd.parent().unwrap();
+        unsafe { env::set_var("HOME", parent) };
+        let name = cwd.file_name().unwrap().to_string_lossy();
+        assert_eq!(render_prompt("\\w", 2), format!("~/{}", name));
+
+        // Color depends on where the prompt goes, not on stdin.
+        unsafe { env::remove_var("NO_COLOR") };
+        let log = File::create("/dev/null").unwrap();
+        assert_eq!(render_prompt("\\w", log.as_raw_fd()), format!("~/{}", name));
+    }
+
+    #[test]
+    fn user_and_host() {
+        let user = User::from_uid(getuid()).unwrap().unwrap().name;
+        assert_eq!(render_prompt("\\u@x", 2), format!("{}@x", user));
+
+        let host = gethostname().unwrap().to_string_lossy().into_owned();
+        let short = render_prompt("\\h", 2);
+        assert_eq!(short, host.split('.').next().unwrap());
+        assert!(!short.contains('.'));
+    }
+
+    #[test]
+    fn literal_escapes() {
+        assert_eq!(render_prompt("\\$ ", 2), "$ ");
+        assert_eq!(render_prompt("a\\\\b", 2), "a\\b");
+        assert_eq!(render_prompt("plain> ", 2), "plain> ");
+    }
+
+    #[test]
+    fn unknown_escapes_stay_literal() {
+        assert_eq!(render_prompt("\\q>", 2), "\\q>");
+        assert_eq!(render_prompt("end\\", 2), "end\\");
+        assert_eq!(render_prompt("\\\\$", 2), "\\$");
+    }
+}
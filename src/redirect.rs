@@ -0,0 +1,233 @@
+use nix::errno::Errno;
+use nix::fcntl::{fcntl, open, FcntlArg, OFlag};
+use nix::sys::stat::{stat, Mode, SFlag};
+use nix::unistd::{close, dup2, lseek, mkstemp, unlink, write, Whence};
+use std::os::unix::io::RawFd;
+use crate::error::ShellError;
+use crate::expand::{expand_heredoc, expand_word};
+use crate::shell::Shell;
+use crate::tokenizer::Word;
+
+#[derive(Clone)]
+pub enum RedirectOp {
+    Read,
+    Write,
+    /// `>|`, which overwrites the file even with noclobber set.
+    Clobber,
+    Append,
+    /// Reads a here-document; the target is the number of its body.
+    HereDoc,
+    /// Reads the target word and a newline.
+    HereString,
+    /// Makes the descriptor a copy of the one numbered by the target, or
+    /// closes it if the target is `-`.
+    Duplicate,
+}
+
+#[derive(Clone)]
+pub struct Redirection {
+    pub fd: RawFd,
+    pub op: RedirectOp,
+    pub target: Word,
+}
+
+/// Takes the redirection operators and the words after them, their
+/// targets, out of `words`, returning the remaining words and the
+/// redirections in order.
+pub fn parse_redirections(words: Vec<Word>) -> Result<(Vec<Word>, Vec<Redirection>), ShellError> {
+    let mut remaining = Vec::new();
+    let mut redirections = Vec::new();
+    let mut words = words.into_iter();
+
+    while let Some(word) = words.next() {
+        if !word.operator {
+            remaining.push(word);
+            continue;
+        }
+        let Some(target) = words.next().filter(|target| !target.operator) else {
+            return Err(ShellError::Syntax("missing redirection target".to_string()));
+        };
+        let operator = &word.segments[0].text;
+        if let Some(both) = operator.strip_prefix('&') {
+            // The file is opened once, for stdout, and stderr made a copy.
+            let op = if both == ">>" { RedirectOp::Append } else { RedirectOp::Write };
+            redirections.push(Redirection { fd: 1, op, target });
+            redirections.push(Redirection { fd: 2, op: RedirectOp::Duplicate, target: Word::literal("1") });
+            continue;
+        }
+        let digits = operator.chars().take_while(char::is_ascii_digit).count();
+        let (op, default_fd) = match &operator[digits..] {
+            "<<<" => (RedirectOp::HereString, 0),
+            "<<" => (RedirectOp::HereDoc, 0),
+            "<&" => (RedirectOp::Duplicate, 0),
+            "<" => (RedirectOp::Read, 0),
+            ">>" => (RedirectOp::Append, 1),
+            ">|" => (RedirectOp::Clobber, 1),
+            ">&" => (RedirectOp::Duplicate, 1),
+            _ => (RedirectOp::Write, 1),
+        };
+        let fd = match &operator[..digits] {
+            "" => default_fd,
+            number => number.parse().map_err(|_| ShellError::Redirect(number.to_string(), Errno::EBADF))?,
+        };
+        redirections.push(Redirection { fd, op, target });
+    }
+
+    Ok((remaining, redirections))
+}
+
+pub fn apply_redirections(redirections: &[Redirection], shell: &Shell) -> Result<(), ShellError> {
+    for redirection in redirections {
+        let flags = match redirection.op {
+            RedirectOp::Read => OFlag::O_RDONLY,
+            RedirectOp::Write if shell.options.noclobber => OFlag::O_CREAT | OFlag::O_WRONLY | OFlag::O_EXCL,
+            RedirectOp::Write | RedirectOp::Clobber => OFlag::O_CREAT | OFlag::O_WRONLY | OFlag::O_TRUNC,
+            RedirectOp::Append => OFlag::O_CREAT | OFlag::O_WRONLY | OFlag::O_APPEND,
+            RedirectOp::HereDoc => {
+                let number = redirection.target.source();
+                let heredoc = number.parse().ok().and_then(|i: usize| shell.heredocs.get(i));
+                let Some(heredoc) = heredoc else {
+                    return Err(ShellError::Syntax("here-document without a body".to_string()));
+                };
+                let body = if heredoc.expand { expand_heredoc(&heredoc.body, shell)? } else { heredoc.body.clone() };
+                let fd = heredoc_fd(&body).map_err(|err| ShellError::Redirect("here-document".to_string(), err))?;
+                move_fd(fd, redirection.fd)?;
+                continue;
+            }
+            RedirectOp::Duplicate => {
+                let target = single_word(&redirection.target, shell)?;
+                if target == "-" {
+                    let _ = close(redirection.fd);
+                    continue;
+                }
+                let Ok(fd) = target.parse::<RawFd>() else {
+                    return Err(ShellError::AmbiguousRedirect(target));
+                };
+                dup2(fd, redirection.fd).map_err(|err| ShellError::Redirect(target, err))?;
+                continue;
+            }
+            RedirectOp::HereString => {
+                let mut text = expand_word(&redirection.target, shell)?.join(" ");
+                text.push('\n');
+                let fd = heredoc_fd(&text).map_err(|err| ShellError::Redirect("here-string".to_string(), err))?;
+                move_fd(fd, redirection.fd)?;
+                continue;
+            }
+        };
+        let target = single_word(&redirection.target, shell)?;
+        // Like sh, new files get 0666 less the umask.
+        let fd = match open(target.as_str(), flags, Mode::from_bits_truncate(0o666)) {
+            // Noclobber only protects regular files, so `> /dev/null` works.
+            Err(Errno::EEXIST) if is_regular(&target) => return Err(ShellError::Clobber(target)),
+            Err(Errno::EEXIST) => open(target.as_str(), OFlag::O_WRONLY, Mode::empty()),
+            result => result,
+        }
+        .map_err(|err| ShellError::Redirect(target.clone(), err))?;
+        if fd != redirection.fd {
+            move_fd(fd, redirection.fd)?;
+        }
+    }
+    Ok(())
+}
+
+/// Makes `to` refer to what `fd` does and closes `fd`, which is closed even
+/// if `to` can't be used, as with a number past the descriptor limit.
+fn move_fd(fd: RawFd, to: RawFd) -> Result<(), ShellError> {
+    let result = dup2(fd, to).map_err(|err| ShellError::Redirect(to.to_string(), err));
+    let _ = close(fd);
+    result.map(drop)
+}
+
+/// Copies of the descriptors a builtin's redirections replace, for putting
+/// the shell's own back once it's done.
+pub struct SavedFds(Vec<(RawFd, Option<RawFd>)>);
+
+/// Sets aside each descriptor `redirections` will replace. One that isn't
+/// open is remembered as such, and closed again on restore.
+pub fn save_fds(redirections: &[Redirection]) -> SavedFds {
+    // The copies go above every descriptor the redirections name, which
+    // would otherwise be free to replace one, and aren't inherited by
+    // programs the builtin runs.
+    let named = redirections.iter().flat_map(|redirection| {
+        let target = match redirection.op {
+            RedirectOp::Duplicate => redirection.target.source().parse().ok(),
+            _ => None,
+        };
+        [Some(redirection.fd), target]
+    });
+    let named: Vec<RawFd> = named.flatten().collect();
+    let mut saved: Vec<(RawFd, Option<RawFd>)> = Vec::new();
+    for redirection in redirections {
+        if saved.iter().any(|&(fd, _)| fd == redirection.fd) {
+            continue;
+        }
+        saved.push((redirection.fd, copy_above(redirection.fd, &named)));
+    }
+    SavedFds(saved)
+}
+
+/// Copies `fd` to a descriptor above 10 and every one in `named` that can
+/// be open at all. Those past the descriptor limit can't, and their
+/// redirections will fail, so the copy goes below them. `None` means `fd`
+/// isn't open.
+fn copy_above(fd: RawFd, named: &[RawFd]) -> Option<RawFd> {
+    let above = |limit: RawFd| named.iter().filter(|&&n| n < limit).fold(10, |base, &n| base.max(n.saturating_add(1)));
+    let mut base = above(RawFd::MAX);
+    loop {
+        match fcntl(fd, FcntlArg::F_DUPFD_CLOEXEC(base)) {
+            Ok(copy) => return Some(copy),
+            Err(Errno::EINVAL) if base > 10 => base = above(base - 1),
+            Err(_) => return None,
+        }
+    }
+}
+
+impl SavedFds {
+    pub fn restore(self) {
+        for (fd, copy) in self.0.into_iter().rev() {
+            match copy {
+                Some(copy) => {
+                    let _ = dup2(copy, fd);
+                    let _ = close(copy);
+                }
+                None => {
+                    let _ = close(fd);
+                }
+            }
+        }
+    }
+}
+
+fn is_regular(path: &str) -> bool {
+    stat(path).is_ok_and(|st| st.st_mode & SFlag::S_IFMT.bits() == SFlag::S_IFREG.bits())
+}
+
+/// Expands a redirection target, which must come to exactly one word.
+fn single_word(target: &Word, shell: &Shell) -> Result<String, ShellError> {
+    match expand_word(target, shell)?.as_slice() {
+        [word] => Ok(word.clone()),
+        _ => Err(ShellError::AmbiguousRedirect(target.source())),
+    }
+}
+
+/// Writes `body` to an unlinked temporary file, which unlike a pipe can't
+/// fill up before anything reads it, and returns a descriptor for
+/// reading it from the start.
+fn heredoc_fd(body: &str) -> nix::Result<RawFd> {
+    let (fd, path) = mkstemp("/tmp/mini-shell-heredoc-XXXXXX")?;
+    let _ = unlink(&path);
+    let written = write_all(fd, body.as_bytes()).and_then(|_| lseek(fd, 0, Whence::SeekSet));
+    if let Err(err) = written {
+        let _ = close(fd);
+        return Err(err);
+    }
+    Ok(fd)
+}
+
+fn write_all(fd: RawFd, mut bytes: &[u8]) -> nix::Result<()> {
+    while !bytes.is_empty() {
+        let written = write(fd, bytes)?;
+        bytes = &bytes[written..];
+    }
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use nix::unistd::Pid;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use crate::builtins::try_run_builtin;
+use crate::color;
+use crate::executor::run;
+use crate::heredoc::{take_heredocs, HereDoc};
+use crate::history::History;
+use crate::input::{read_command, LineEditor, LineSource, Terminal};
+use crate::jobs::Jobs;
+use crate::parser::parse;
+use crate::prompt;
+use crate::signals;
+
+/// State that persists across the commands of a session.
+pub struct Shell {
+    pub aliases: HashMap<String, String>,
+    /// Directories saved by `pushd`, the most recent last.
+    pub dir_stack: Vec<PathBuf>,
+    /// Set once `exit`, or a failure with errexit set, asks for the shell to
+    /// end. Nothing more runs until `finish` is called.
+    pub(crate) exiting: bool,
+    /// Bodies of the here-documents used by the line being run.
+    pub(crate) heredocs: Vec<HereDoc>,
+    pub(crate) history: History,
+    /// Whether commands are being read from a terminal, where a mistake
+    /// shouldn't end the session.
+    pub(crate) interactive: bool,
+    pub(crate) jobs: Jobs,
+    /// The process ID of the last command started in the background, for
+    /// `$!`.
+    pub last_background: Option<Pid>,
+    pub last_status: i32,
+    pub options: ShellOptions,
+    /// The shell's process ID, for `$$`. Subshells keep their parent's.
+    pub pid: i32,
+    /// `$0` followed by the positional parameters `$1`, `$2`, ...
+    pub positional: Vec<String>,
+    /// The command to run for each trapped signal, by number, with 0 for
+    /// the EXIT trap. An empty command means the signal is ignored.
+    pub traps: BTreeMap<i32, String>,
+}
+
+impl Default for Shell {
+    fn default() -> Shell {
+        Shell::new()
+    }
+}
+
+impl Shell {
+    pub fn new() -> Shell {
+        Shell {
+            aliases: HashMap::new(),
+            dir_stack: Vec::new(),
+            exiting: false,
+            heredocs: Vec::new(),
+            history: History::default(),
+            interactive: false,
+            jobs: Jobs::default(),
+            last_background: None,
+            last_status: 0,
+            options: ShellOptions::default(),
+            pid: nix::unistd::getpid().as_raw(),
+            positional: Vec::new(),
+            traps: BTreeMap::new(),
+        }
+    }
+
+    /// Runs one line of input, which may hold several commands, and
+    /// returns the status of the last one. That's also left in `$?`. If one
+    /// of them asked the shell to end, `exit_requested` says so.
+    pub fn run_line(&mut self, input: &str) -> i32 {
+        // The bodies stay numbered after any already held by a caller's line.
+        let first = self.heredocs.len();
+        let commands = take_heredocs(input, first);
+        self.heredocs.extend(commands.heredocs);
+
+        match parse(&commands.text, &self.aliases) {
+            Ok(node) => {
+                run(&node, self);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                self.last_status = 2;
+            }
+        }
+        self.heredocs.truncate(first);
+        self.last_status
+    }
+
+    /// Runs every command in `reader` without prompting, stopping at the
+    /// first read error other than a line that can't be used, or at an
+    /// `exit`. `name` is the script's name in error messages. Returns the
+    /// last status.
+    pub fn run_script(&mut self, mut reader: &mut dyn BufRead, name: &str) -> i32 {
+        loop {
+            match read_command(&mut reader, "") {
+                Ok(Some(input)) => {
+                    self.run_line(&input);
+                    self.run_traps();
+                    if self.exiting {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                    eprintln!("mini-shell: {}: {}", name, err);
+                    self.last_status = 1;
+                }
+                Err(err) => {
+                    eprintln!("mini-shell: {}: {}", name, err);
+                    break;
+                }
+            }
+        }
+        self.last_status
+    }
+
+    /// Runs the commands in the file at `path`, as `source` does, and
+    /// returns the last status.
+    pub fn source(&mut self, path: &str) -> i32 {
+        try_run_builtin(self, &["source".to_string(), path.to_string()]).unwrap_or(1)
+    }
+
+    /// Whether `exit`, or a failure with errexit or nounset set, has asked
+    /// for the shell to end. Once it has, nothing more runs: the caller
+    /// should call `finish` and exit with the status that gives.
+    pub fn exit_requested(&self) -> bool {
+        self.exiting
+    }
+
+    /// Reads and runs commands from stdin with a prompt and history until
+    /// end of input or `exit`, then finishes the session and returns the
+    /// status to exit with. With a terminal that also means job control
+    /// and line editing. `rc_file` is sourced once that's set up, before
+    /// the first prompt.
+    pub fn run_interactive(&mut self, rc_file: Option<&str>) -> i32 {
+        signals::install_handlers();
+        self.history.load();
+        let mut terminal: Box<dyn LineSource> = Box::new(Terminal);
+        if nix::unistd::isatty(0).unwrap_or(false) {
+            self.interactive = true;
+            self.jobs.enable_control();
+            // Fall back to plain reads if the terminal can't be put in raw mode.
+            if let Ok(mut editor) = LineEditor::new() {
+                for entry in self.history.entries() {
+                    editor.add_history(entry);
+                }
+                terminal = Box::new(editor);
+            }
+        }
+        // Errors in the file are reported by the commands that hit them.
+        if let Some(path) = rc_file {
+            self.source(path);
+        }
+        loop {
+            self.jobs.reap();
+            self.run_traps();
+            // An `exit` in the rc file, a trap or the last line ends the
+            // session here.
+            if self.exiting {
+                return self.finish();
+            }
+            // A Ctrl+C that interrupted the last command is dealt with already.
+            signals::take_interrupt();
+
+            let prompt = prompt::prompt(self.last_status, terminal.prompt_fd());
+            match read_command(terminal.as_mut(), &prompt) {
+                Ok(Some(input)) => {
+                    // History events are replaced before anything else reads
+                    // the line, and the result is shown as it will run.
+                    let input = match self.history.expand(&input) {
+                        Ok(Some(expanded)) => {
+                            print!("{}", expanded);
+                            expanded
+                        }
+                        Ok(None) => input,
+                        Err(event) => {
+                            eprintln!("{}", color::error(&format!("mini-shell: {}: event not found", event)));
+                            continue;
+                        }
+                    };
+                    if self.history.add(&input) {
+                        terminal.add_history(&input);
+                    }
+                    self.run_line(&input);
+                }
+                // End of input (Ctrl+D on an empty line) ends the session with
+                // the status of the last command.
+                Ok(None) => return self.finish(),
+                // Ctrl+C at the prompt drops the line and starts a fresh one.
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    if signals::take_interrupt() {
+                        eprintln!();
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                    eprintln!("mini-shell: {}", err);
+                    self.last_status = 1;
+                }
+                Err(_) => eprintln!("Failed to read line"),
+            }
+        }
+    }
+
+    /// Ends the session: runs the EXIT trap and saves history, then returns
+    /// the status the process should exit with. That's the last command's,
+    /// unless the trap runs `exit` with another.
+    pub fn finish(&mut self) -> i32 {
+        let mut status = self.last_status;
+        self.exiting = false;
+        // Taken out first, so it can't run twice.
+        if let Some(command) = self.traps.remove(&0) {
+            let code = self.run_line(&command);
+            if self.exiting {
+                status = code;
+            }
+        }
+        self.exiting = false;
+        self.history.save();
+        status
+    }
+
+    /// Runs the trap command for each trapped signal that has arrived since
+    /// the last call, leaving `$?` as it was.
+    fn run_traps(&mut self) {
+        for signal in signals::take_trapped() {
+            if let Some(command) = self.traps.get(&signal).cloned() {
+                let status = self.last_status;
+                self.run_line(&command);
+                self.last_status = status;
+            }
+        }
+    }
+
+    /// A copy of this shell for running a command substitution or a
+    /// background list. It keeps the aliases, options, directory stack and
+    /// here-documents, but has no jobs, job control or traps and doesn't keep
+    /// history.
+    pub fn subshell(&self) -> Shell {
+        Shell {
+            aliases: self.aliases.clone(),
+            dir_stack: self.dir_stack.clone(),
+            heredocs: self.heredocs.clone(),
+            last_background: self.last_background,
+            last_status: self.last_status,
+            options: self.options.clone(),
+            pid: self.pid,
+            positional: self.positional.clone(),
+            ..Shell::default()
+        }
+    }
+}
+
+
+/// The options turned on with `set -o name` or `set -X` and off with `+`.
+#[derive(Clone, Default)]
+pub struct ShellOptions {
+    /// A failing command ends the shell, unless its status is tested by
+    /// `&&` or `||`.
+    pub errexit: bool,
+    /// `>` won't overwrite an existing file; `>|` still does.
+    pub noclobber: bool,
+    /// Expanding an unset variable is an error.
+    pub nounset: bool,
+    /// Each command is printed to stderr, after expansion, before it runs.
+    pub xtrace: bool,
+}
+
+impl ShellOptions {
+    /// Every option's name, with the letter that stands for it.
+    pub const NAMES: &[(&str, char)] = &[("errexit", 'e'), ("noclobber", 'C'), ("nounset", 'u'), ("xtrace", 'x')];
+
+    pub fn flag(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "errexit" => Some(&mut self.errexit),
+            "noclobber" => Some(&mut self.noclobber),
+            "nounset" => Some(&mut self.nounset),
+            "xtrace" => Some(&mut self.xtrace),
+            _ => None,
+        }
+    }
+}
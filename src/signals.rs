@@ -0,0 +1,168 @@
+use nix::errno::Errno;
+use nix::fcntl::OFlag;
+use nix::libc::c_int;
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use nix::unistd::{pipe2, read, write};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// Set once the interactive handlers are in, which is what `trap - sig`
+/// goes back to.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+/// The read and write ends of the pipe trapped signals are written to, since
+/// a handler can't safely do more than that.
+static TRAP_PIPE: [AtomicI32; 2] = [AtomicI32::new(-1), AtomicI32::new(-1)];
+/// Bit `n` is set while signal `n` is caught by a trap, or ignored by one.
+static CAUGHT: AtomicU64 = AtomicU64::new(0);
+static IGNORED: AtomicU64 = AtomicU64::new(0);
+
+extern "C" fn on_sigint(_: c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+extern "C" fn on_trapped(signal: c_int) {
+    if signal == Signal::SIGINT as c_int {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    queue(signal);
+}
+
+fn queue(signal: c_int) {
+    let fd = TRAP_PIPE[1].load(Ordering::SeqCst);
+    if fd >= 0 {
+        let _ = write(fd, &[signal as u8]);
+    }
+}
+
+/// What a `trap` makes of a signal.
+pub enum Disposition {
+    /// Queue the signal for its trap command.
+    Catch,
+    Ignore,
+    /// Go back to how the shell handles it without a trap.
+    Default,
+}
+
+/// Catches SIGINT so Ctrl+C doesn't kill the shell. The handler is installed
+/// without `SA_RESTART`, so a blocked read or wait returns `EINTR` instead of
+/// silently resuming. The job-control signals are ignored so Ctrl+Z and
+/// terminal handoffs never stop the shell itself.
+pub fn install_handlers() {
+    INSTALLED.store(true, Ordering::SeqCst);
+    let action = SigAction::new(SigHandler::Handler(on_sigint), SaFlags::empty(), SigSet::empty());
+    let _ = unsafe { sigaction(Signal::SIGINT, &action) };
+
+    let ignore = SigAction::new(SigHandler::SigIgn, SaFlags::empty(), SigSet::empty());
+    for signal in [Signal::SIGTSTP, Signal::SIGTTOU, Signal::SIGTTIN] {
+        let _ = unsafe { sigaction(signal, &ignore) };
+    }
+}
+
+/// Restores default signal dispositions in a freshly forked child, including
+/// SIGPIPE, which the Rust runtime ignores and children would otherwise inherit.
+/// Trapped signals go back to the default too, but ones a trap ignores stay
+/// ignored, as in sh.
+pub fn reset_child_signals() {
+    let default = SigAction::new(SigHandler::SigDfl, SaFlags::empty(), SigSet::empty());
+    let caught = Signal::iterator().filter(|&signal| is_set(&CAUGHT, signal));
+    let standard = [Signal::SIGINT, Signal::SIGPIPE, Signal::SIGTSTP, Signal::SIGTTOU, Signal::SIGTTIN];
+    for signal in standard.into_iter().chain(caught) {
+        if !is_set(&IGNORED, signal) {
+            let _ = unsafe { sigaction(signal, &default) };
+        }
+    }
+}
+
+/// Sets how `signal` is handled for a `trap`.
+pub fn set_trap(signal: Signal, disposition: Disposition) -> nix::Result<()> {
+    let handler = match disposition {
+        Disposition::Catch => {
+            if TRAP_PIPE[0].load(Ordering::SeqCst) < 0 {
+                let (read_end, write_end) = pipe2(OFlag::O_NONBLOCK | OFlag::O_CLOEXEC)?;
+                TRAP_PIPE[0].store(read_end, Ordering::SeqCst);
+                TRAP_PIPE[1].store(write_end, Ordering::SeqCst);
+            }
+            SigHandler::Handler(on_trapped)
+        }
+        Disposition::Ignore => SigHandler::SigIgn,
+        Disposition::Default if INSTALLED.load(Ordering::SeqCst) => match signal {
+            Signal::SIGINT => SigHandler::Handler(on_sigint),
+            Signal::SIGTSTP | Signal::SIGTTOU | Signal::SIGTTIN => SigHandler::SigIgn,
+            _ => SigHandler::SigDfl,
+        },
+        Disposition::Default => SigHandler::SigDfl,
+    };
+    let action = SigAction::new(handler, SaFlags::empty(), SigSet::empty());
+    unsafe { sigaction(signal, &action) }?;
+
+    let bit = 1 << signal as u32;
+    let (caught, ignored) = match disposition {
+        Disposition::Catch => (true, false),
+        Disposition::Ignore => (false, true),
+        Disposition::Default => (false, false),
+    };
+    for (mask, set) in [(&CAUGHT, caught), (&IGNORED, ignored)] {
+        if set {
+            mask.fetch_or(bit, Ordering::SeqCst);
+        } else {
+            mask.fetch_and(!bit, Ordering::SeqCst);
+        }
+    }
+    Ok(())
+}
+
+fn is_set(mask: &AtomicU64, signal: Signal) -> bool {
+    mask.load(Ordering::SeqCst) & (1 << signal as u32) != 0
+}
+
+/// Queues `signal` for its trap when the shell learns of it some other way
+/// than a handler, if a trap catches it.
+pub fn queue_trap(signal: Signal) {
+    if is_set(&CAUGHT, signal) {
+        queue(signal as c_int);
+    }
+}
+
+/// The trapped signals that have arrived since the last call, in order.
+pub fn take_trapped() -> Vec<c_int> {
+    let fd = TRAP_PIPE[0].load(Ordering::SeqCst);
+    let mut signals = Vec::new();
+    if fd < 0 {
+        return signals;
+    }
+    let mut buf = [0; 64];
+    loop {
+        match read(fd, &mut buf) {
+            Ok(0) | Err(Errno::EAGAIN) => break,
+            Ok(len) => signals.extend(buf[..len].iter().map(|&signal| signal as c_int)),
+            Err(Errno::EINTR) => {}
+            Err(_) => break,
+        }
+    }
+    signals
+}
+
+/// Whether Ctrl+C has been pressed since the flag was last taken, leaving it
+/// set.
+pub fn interrupt_pending() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Reports whether SIGINT arrived since the last call.
+pub fn take_interrupt() -> bool {
+    INTERRUPTED.swap(false, Ordering::SeqCst)
+}
+
+/// Parses a signal given by number or by name, with or without the `SIG`
+/// prefix and in either case, as `kill` takes them.
+pub fn parse_signal(text: &str) -> Option<Signal> {
+    if let Ok(number) = text.parse::<c_int>() {
+        return Signal::try_from(number).ok();
+    }
+    let name = text.to_ascii_uppercase();
+    if name.starts_with("SIG") {
+        name.parse().ok()
+    } else {
+        format!("SIG{}", name).parse().ok()
+    }
+}
@@ -0,0 +1,109 @@
+use nix::unistd::{close, dup2, pipe, ForkResult, Pid};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::process::exit;
+use std::sync::Mutex;
+use crate::jobs::{fork_failed, fork_retrying, wait_for};
+use crate::shell::Shell;
+use crate::signals;
+
+/// Runs `command` in a subshell and returns what it wrote to stdout, minus
+/// any trailing newlines and, as in bash, any NUL bytes.
+pub fn command_output(command: &str, shell: &Shell) -> String {
+    let (read_end, write_end) = match pipe() {
+        Ok(fds) => fds,
+        Err(err) => {
+            eprintln!("Pipe failed: {}", err);
+            return String::new();
+        }
+    };
+
+    match unsafe { fork_retrying() } {
+        Ok(ForkResult::Child) => {
+            signals::reset_child_signals();
+            let _ = close(read_end);
+            let _ = dup2(write_end, 1);
+            let _ = close(write_end);
+            let mut subshell = shell.subshell();
+            subshell.run_line(command);
+            let _ = io::stdout().flush();
+            exit(subshell.last_status);
+        }
+        Ok(ForkResult::Parent { child }) => {
+            let _ = close(write_end);
+            let mut output = Vec::new();
+            let _ = unsafe { File::from_raw_fd(read_end) }.read_to_end(&mut output);
+            wait_for(child);
+
+            output.retain(|&byte| byte != 0);
+            let mut output = String::from_utf8_lossy(&output).into_owned();
+            output.truncate(output.trim_end_matches('\n').len());
+            output
+        }
+        Err(err) => {
+            fork_failed(&format!("$({})", command), err);
+            let _ = close(read_end);
+            let _ = close(write_end);
+            String::new()
+        }
+    }
+}
+
+/// The shell's end of each process substitution made for the command being
+/// expanded, with the process on the other end.
+static PROCESSES: Mutex<Vec<(RawFd, Pid)>> = Mutex::new(Vec::new());
+
+/// Starts `command` in a subshell connected to a pipe and returns a path for
+/// the other end: one that reads its output, or with `output` set, one that
+/// writes to its input. The pipe stays open until `finish_processes`.
+pub fn process_substitution(command: &str, output: bool, shell: &Shell) -> String {
+    let (read_end, write_end) = match pipe() {
+        Ok(fds) => fds,
+        Err(err) => {
+            eprintln!("Pipe failed: {}", err);
+            return String::new();
+        }
+    };
+    let (ours, theirs) = if output { (write_end, read_end) } else { (read_end, write_end) };
+
+    match unsafe { fork_retrying() } {
+        Ok(ForkResult::Child) => {
+            signals::reset_child_signals();
+            // Earlier substitutions' pipes would otherwise be held open.
+            for &(fd, _) in PROCESSES.lock().unwrap().iter() {
+                let _ = close(fd);
+            }
+            let _ = close(ours);
+            let _ = dup2(theirs, if output { 0 } else { 1 });
+            let _ = close(theirs);
+            let mut subshell = shell.subshell();
+            subshell.run_line(command);
+            let _ = io::stdout().flush();
+            exit(subshell.last_status);
+        }
+        Ok(ForkResult::Parent { child }) => {
+            let _ = close(theirs);
+            PROCESSES.lock().unwrap().push((ours, child));
+            format!("/dev/fd/{}", ours)
+        }
+        Err(err) => {
+            fork_failed(&format!("{}({})", if output { '>' } else { '<' }, command), err);
+            let _ = close(ours);
+            let _ = close(theirs);
+            String::new()
+        }
+    }
+}
+
+/// Closes the pipes made by process substitutions once the command using
+/// them has run, then waits for their processes unless the command was
+/// left running in the background.
+pub fn finish_processes(wait: bool) {
+    for (fd, child) in PROCESSES.lock().unwrap().drain(..) {
+        let _ = close(fd);
+        if wait {
+            wait_for(child);
+        }
+    }
+}
@@ -0,0 +1,527 @@
+use crate::error::ShellError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+/// How the command in a substitution segment is used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Substitution {
+    /// `$(...)` or `` `...` ``: its output replaces it.
+    Command,
+    /// `<(...)`: replaced by a path its output can be read from.
+    Input,
+    /// `>(...)`: replaced by a path that writes to its input.
+    Output,
+}
+
+/// A run of characters within a word that share the same quoting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub text: String,
+    pub quote: Quote,
+    /// `text` is a command to run rather than text.
+    pub substitution: Option<Substitution>,
+}
+
+/// A single shell word. Quoting is kept per segment so that later expansion
+/// passes know which parts they are allowed to touch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Word {
+    pub segments: Vec<Segment>,
+    /// The word is a redirection operator such as `<`, `>>` or `2>&`, not text.
+    pub operator: bool,
+}
+
+impl Word {
+    fn push(&mut self, c: char, quote: Quote) {
+        match self.segments.last_mut() {
+            Some(segment) if segment.quote == quote && segment.substitution.is_none() => segment.text.push(c),
+            _ => self.segments.push(Segment { text: c.to_string(), quote, substitution: None }),
+        }
+    }
+
+    fn push_substitution(&mut self, command: String, quote: Quote, kind: Substitution) {
+        self.segments.push(Segment { text: command, quote, substitution: Some(kind) });
+    }
+
+    fn operator(text: String) -> Word {
+        Word { segments: vec![Segment { text, quote: Quote::None, substitution: None }], operator: true }
+    }
+
+    /// A word that expands to `text` as it is.
+    pub fn literal(text: &str) -> Word {
+        Word { segments: vec![Segment { text: text.to_string(), quote: Quote::Single, substitution: None }], operator: false }
+    }
+
+    /// The word as it might have been written, for error messages.
+    pub fn source(&self) -> String {
+        let mut source = String::new();
+        for segment in &self.segments {
+            match (segment.quote, segment.substitution) {
+                (_, Some(Substitution::Command)) => source.push_str(&format!("$({})", segment.text)),
+                (_, Some(Substitution::Input)) => source.push_str(&format!("<({})", segment.text)),
+                (_, Some(Substitution::Output)) => source.push_str(&format!(">({})", segment.text)),
+                (Quote::Single, None) => source.push_str(&format!("'{}'", segment.text)),
+                (Quote::Double, None) => source.push_str(&format!("\"{}\"", segment.text)),
+                (Quote::None, None) => source.push_str(&segment.text),
+            }
+        }
+        source
+    }
+
+    /// Marks a quoted span, which yields a word even when the quotes are empty.
+    fn open(&mut self, quote: Quote) {
+        if self.segments.last().is_none_or(|segment| segment.quote != quote) {
+            self.segments.push(Segment { text: String::new(), quote, substitution: None });
+        }
+    }
+}
+
+/// Splits `line` into words on unquoted whitespace. Single quotes keep their
+/// contents literal; double quotes group their contents but still allow
+/// variable expansion. A backslash outside single quotes makes the next
+/// character literal, and a trailing backslash is a line continuation.
+/// Command substitutions are kept whole, outside or inside double quotes.
+/// Unquoted redirection operators end the word before them and become words
+/// of their own, taking a number right before them as the descriptor they
+/// redirect.
+pub fn tokenize(line: &str) -> Result<Vec<Word>, ShellError> {
+    let mut words = Vec::new();
+    let mut current: Option<Word> = None;
+    let mut chars = line.chars();
+
+    loop {
+        // Arithmetic is expanded along with variables, so it stays in the
+        // word's text, spaces and all.
+        if let Some(len) = arithmetic_len(chars.as_str()) {
+            let (expression, rest) = chars.as_str().split_at(len);
+            let word = current.get_or_insert_default();
+            expression.chars().for_each(|c| word.push(c, Quote::None));
+            chars = rest.chars();
+            continue;
+        }
+        if let Some(substitution) = split_substitution(chars.as_str()) {
+            let (command, rest) = substitution?;
+            current.get_or_insert_default().push_substitution(command, Quote::None, Substitution::Command);
+            chars = rest.chars();
+            continue;
+        }
+        if let Some(process) = split_process(chars.as_str()) {
+            let (kind, command, rest) = process?;
+            current.get_or_insert_default().push_substitution(command, Quote::None, kind);
+            chars = rest.chars();
+            continue;
+        }
+        let Some(c) = chars.next() else {
+            break;
+        };
+        match c {
+            '\'' | '"' => {
+                let quote = if c == '\'' { Quote::Single } else { Quote::Double };
+                let word = current.get_or_insert_default();
+                word.open(quote);
+                loop {
+                    if quote == Quote::Double
+                        && let Some(len) = arithmetic_len(chars.as_str())
+                    {
+                        let (expression, rest) = chars.as_str().split_at(len);
+                        expression.chars().for_each(|c| word.push(c, quote));
+                        chars = rest.chars();
+                        continue;
+                    }
+                    if quote == Quote::Double
+                        && let Some(substitution) = split_substitution(chars.as_str())
+                    {
+                        let (command, rest) = substitution?;
+                        word.push_substitution(command, quote, Substitution::Command);
+                        chars = rest.chars();
+                        continue;
+                    }
+                    match chars.next() {
+                        Some(next) if next == c => break,
+                        Some('\\') if quote == Quote::Double => match chars.next() {
+                            // Inside double quotes only these characters can be escaped.
+                            Some(next @ ('$' | '`' | '"' | '\\')) => word.push(next, Quote::Single),
+                            Some(next) => {
+                                word.push('\\', quote);
+                                word.push(next, quote);
+                            }
+                            None => word.push('\\', quote),
+                        },
+                        Some(next) => word.push(next, quote),
+                        None => {
+                            return Err(ShellError::Syntax(format!(
+                                "unexpected EOF while looking for matching `{}'",
+                                c
+                            )));
+                        }
+                    }
+                }
+            }
+            // Escaped characters are stored as single-quoted so nothing expands them.
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.get_or_insert_default().push(next, Quote::Single);
+                }
+            }
+            c if c.is_whitespace() => {
+                if let Some(word) = current.take() {
+                    words.push(word);
+                }
+            }
+            // `&>` and `&>>` send both stdout and stderr to a file.
+            '&' if chars.as_str().starts_with('>') => {
+                if let Some(word) = current.take() {
+                    words.push(word);
+                }
+                let append = chars.as_str().starts_with(">>");
+                let operator = if append { "&>>" } else { "&>" };
+                chars = chars.as_str()[operator.len() - 1..].chars();
+                words.push(Word::operator(operator.to_string()));
+            }
+            '<' | '>' => {
+                let mut operator = String::new();
+                // A digit straight after an operator is its target, as in
+                // `2>&1>out`, rather than the descriptor for this one.
+                let after_operator = words.last().is_some_and(|word: &Word| word.operator);
+                match current.take() {
+                    Some(word) if is_descriptor(&word) && !after_operator => operator.push_str(&word.segments[0].text),
+                    Some(word) => words.push(word),
+                    None => {}
+                }
+                operator.push(c);
+                // Longest operator first, so `<<<` isn't read as `<<` and `<`.
+                let rest = chars.as_str();
+                let tail = match c {
+                    '<' if rest.starts_with("<<") => "<<",
+                    '<' if rest.starts_with('<') => "<",
+                    '<' if rest.starts_with('&') => "&",
+                    '>' if rest.starts_with('>') => ">",
+                    '>' if rest.starts_with('|') => "|",
+                    '>' if rest.starts_with('&') => "&",
+                    _ => "",
+                };
+                operator.push_str(tail);
+                chars = rest[tail.len()..].chars();
+                words.push(Word::operator(operator));
+            }
+            c => current.get_or_insert_default().push(c, Quote::None),
+        }
+    }
+
+    if let Some(word) = current {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Whether `word` is an unquoted number, which names a descriptor when it
+/// comes right before a redirection operator.
+fn is_descriptor(word: &Word) -> bool {
+    match word.segments.as_slice() {
+        [segment] => {
+            segment.quote == Quote::None
+                && segment.substitution.is_none()
+                && !segment.text.is_empty()
+                && segment.text.chars().all(|c| c.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+/// The length of the `$((...))` arithmetic expansion at the start of `text`,
+/// if there is a complete one.
+pub fn arithmetic_len(text: &str) -> Option<usize> {
+    let body = text.strip_prefix("$((")?;
+    let mut depth = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return body[i + 1..].starts_with(')').then_some(i + 5),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a `$(...)` or `` `...` `` command substitution off the front of
+/// `text`, returning the command inside it and the text that follows. Returns
+/// `None` if `text` doesn't start with one.
+pub fn split_substitution(text: &str) -> Option<Result<(String, &str), ShellError>> {
+    if arithmetic_len(text).is_some() {
+        return None;
+    }
+    let (body, end) = if let Some(body) = text.strip_prefix("$(") {
+        (body, paren_end(body))
+    } else if let Some(body) = text.strip_prefix('`') {
+        (body, backtick_end(body))
+    } else {
+        return None;
+    };
+
+    let Some(end) = end else {
+        let closer = if text.starts_with('`') { '`' } else { ')' };
+        return Some(Err(ShellError::Syntax(format!(
+            "unexpected EOF while looking for matching `{}'",
+            closer
+        ))));
+    };
+    let command = if text.starts_with('`') {
+        // Within backticks a backslash only escapes `$`, `` ` `` and `\`.
+        let mut command = String::new();
+        let mut chars = body[..end].chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some(next @ ('$' | '`' | '\\'))) => {
+                    command.push(next);
+                    chars.next();
+                }
+                _ => command.push(c),
+            }
+        }
+        command
+    } else {
+        body[..end].to_string()
+    };
+    Some(Ok((command, &body[end + 1..])))
+}
+
+/// Splits a `<(...)` or `>(...)` process substitution off the front of
+/// `text`, returning which it is, the command inside it and the text that
+/// follows. Returns `None` if `text` doesn't start with one.
+pub fn split_process(text: &str) -> Option<Result<(Substitution, String, &str), ShellError>> {
+    let kind = match text.get(..2)? {
+        "<(" => Substitution::Input,
+        ">(" => Substitution::Output,
+        _ => return None,
+    };
+    let body = &text[2..];
+    let Some(end) = paren_end(body) else {
+        return Some(Err(ShellError::Syntax("unexpected EOF while looking for matching `)'".to_string())));
+    };
+    Some(Ok((kind, body[..end].to_string(), &body[end + 1..])))
+}
+
+/// Finds the `)` that closes a `$(`, given the text after it. Parentheses
+/// inside quotes don't count.
+fn paren_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = text.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(i),
+            (None, ')') => depth -= 1,
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+/// Finds the unescaped backtick that closes a `` ` ``, given the text after it.
+fn backtick_end(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '`' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The length of the command substitution or arithmetic expansion at the
+/// start of `text`, if there is one. An unterminated substitution runs to the
+/// end of `text`.
+pub fn substitution_len(text: &str) -> Option<usize> {
+    if let Some(len) = arithmetic_len(text) {
+        return Some(len);
+    }
+    match split_substitution(text)? {
+        Ok((_, rest)) => Some(text.len() - rest.len()),
+        Err(_) => Some(text.len()),
+    }
+}
+
+/// Like `substitution_len`, for a process substitution, which is only one
+/// outside quotes.
+fn process_len(text: &str) -> Option<usize> {
+    match split_process(text)? {
+        Ok((_, _, rest)) => Some(text.len() - rest.len()),
+        Err(_) => Some(text.len()),
+    }
+}
+
+/// Finds every occurrence of `operators` in `line` that isn't inside quotes,
+/// returning the byte offset of each along with the operator matched. Earlier
+/// entries in `operators` win when several match at the same position.
+/// Operators inside command substitutions belong to the inner command.
+pub fn find_operators<'a>(line: &str, operators: &[&'a str]) -> Vec<(usize, &'a str)> {
+    let mut found = Vec::new();
+    let mut quote = None;
+    let mut skip_until = 0;
+
+    for (i, c) in line.char_indices() {
+        if i < skip_until {
+            continue;
+        }
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some('"') if c == '\\' => skip_until = i + 2,
+            Some('"') if let Some(len) = substitution_len(&line[i..]) => skip_until = i + len,
+            Some(_) => {}
+            None if c == '\\' => skip_until = i + 2,
+            None if let Some(len) = substitution_len(&line[i..]).or_else(|| process_len(&line[i..])) => {
+                skip_until = i + len
+            }
+            None if c == '\'' || c == '"' => quote = Some(c),
+            // The `|` in `>|` is part of the redirection, not a pipe, and
+            // the `&` in `>&`, `<&` or `&>` doesn't put anything in the
+            // background.
+            None if [">|", ">&", "<&", "&>"].iter().any(|op| line[i..].starts_with(op)) => skip_until = i + 2,
+            None => {
+                if let Some(op) = operators.iter().find(|op| line[i..].starts_with(**op)) {
+                    found.push((i, *op));
+                    skip_until = i + op.len();
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Splits `line` on every unquoted occurrence of any of `separators`.
+pub fn split_unquoted<'a>(line: &'a str, separators: &[&str]) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (pos, op) in find_operators(line, separators) {
+        parts.push(&line[start..pos]);
+        start = pos + op.len();
+    }
+    parts.push(&line[start..]);
+    parts
+}
+
+/// Removes `#` comments from `input`. A comment starts at an unquoted `#`
+/// that begins a word and runs to the end of its line.
+pub fn strip_comments(input: &str) -> String {
+    let mut out = String::new();
+    let mut quote = None;
+    let mut chars = input.chars();
+
+    loop {
+        let rest = chars.as_str();
+        let len = match quote {
+            Some('\'') => None,
+            Some(_) => substitution_len(rest),
+            None => substitution_len(rest).or_else(|| process_len(rest)),
+        };
+        if let Some(len) = len {
+            out.push_str(&rest[..len]);
+            chars = rest[len..].chars();
+            continue;
+        }
+        let Some(c) = chars.next() else {
+            break;
+        };
+        match (quote, c) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => {
+                out.push(c);
+                out.extend(chars.next());
+                continue;
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '#') if out.is_empty() || out.ends_with(|p: char| p.is_whitespace() || ";|&<>(".contains(p)) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push(next);
+                        break;
+                    }
+                }
+                continue;
+            }
+            (None, _) => {}
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub enum LineState {
+    Complete,
+    /// The line ends in an unescaped backslash.
+    Continued,
+    /// A quote or command substitution is still open at the end of the line.
+    OpenQuote,
+}
+
+/// Reports whether `line` is a complete command or needs more input.
+pub fn line_state(line: &str) -> LineState {
+    let mut quote = None;
+    let mut chars = line.trim_end_matches('\n').chars();
+
+    loop {
+        if quote != Some('\'')
+            && let Some(substitution) = split_substitution(chars.as_str())
+        {
+            match substitution {
+                Ok((_, rest)) => chars = rest.chars(),
+                Err(_) => return LineState::OpenQuote,
+            }
+            continue;
+        }
+        if quote.is_none()
+            && let Some(process) = split_process(chars.as_str())
+        {
+            match process {
+                Ok((_, _, rest)) => chars = rest.chars(),
+                Err(_) => return LineState::OpenQuote,
+            }
+            continue;
+        }
+        let Some(c) = chars.next() else {
+            break;
+        };
+        match (quote, c) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => {
+                if chars.next().is_none() {
+                    return LineState::Continued;
+                }
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, _) => {}
+        }
+    }
+
+    if quote.is_some() {
+        LineState::OpenQuote
+    } else {
+        LineState::Complete
+    }
+}
@@ -0,0 +1,84 @@
+use mini_shell::Shell;
+use std::env;
+use std::fs;
+
+// The tests share the process environment, so each uses its own variables
+// and files.
+
+#[test]
+fn run_line_returns_the_status() {
+    let mut shell = Shell::new();
+    assert_eq!(shell.run_line("true"), 0);
+    assert_eq!(shell.run_line("false"), 1);
+    assert_eq!(shell.last_status, 1);
+    assert_eq!(shell.run_line("sh -c 'exit 3'"), 3);
+    assert_eq!(shell.run_line("false || true"), 0);
+    assert_eq!(shell.run_line("echo unclosed 'quote"), 2);
+}
+
+#[test]
+fn state_carries_over_between_lines() {
+    let mut shell = Shell::new();
+    shell.run_line("LIBRARY_COUNT=4");
+    shell.run_line("LIBRARY_COUNT=$((LIBRARY_COUNT * 2))");
+    assert_eq!(env::var("LIBRARY_COUNT").as_deref(), Ok("8"));
+
+    shell.run_line("alias library_set='LIBRARY_ALIAS=ran'");
+    assert!(shell.aliases.contains_key("library_set"));
+    shell.run_line("library_set");
+    assert_eq!(env::var("LIBRARY_ALIAS").as_deref(), Ok("ran"));
+
+    shell.run_line("sh -c 'exit 5'");
+    shell.run_line("LIBRARY_STATUS=$?");
+    assert_eq!(env::var("LIBRARY_STATUS").as_deref(), Ok("5"));
+}
+
+#[test]
+fn run_script_runs_every_line() {
+    let path = env::temp_dir().join(format!("mini-shell-library-{}.txt", std::process::id()));
+    // The test harness captures what builtins print, so `printf` writes the file.
+    let script = format!("LIBRARY_FIRST=one\nprintf '%s\\n' \"$LIBRARY_FIRST two\" > {}\nfalse\n", path.display());
+    let mut shell = Shell::new();
+    assert_eq!(shell.run_script(&mut script.as_bytes(), "script"), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one two\n");
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn positional_parameters() {
+    let mut shell = Shell::new();
+    shell.positional = vec!["embedded".to_string(), "first".to_string(), "second".to_string()];
+    shell.run_line("LIBRARY_ARGS=\"$0:$2:$#\"");
+    assert_eq!(env::var("LIBRARY_ARGS").as_deref(), Ok("embedded:second:2"));
+}
+
+#[test]
+fn exit_is_returned_to_the_caller() {
+    let mut shell = Shell::new();
+    assert_eq!(shell.run_line("true"), 0);
+    assert!(!shell.exit_requested());
+    assert_eq!(shell.run_line("exit 4; LIBRARY_AFTER_EXIT=ran"), 4);
+    assert!(shell.exit_requested());
+    assert!(env::var("LIBRARY_AFTER_EXIT").is_err());
+    assert_eq!(shell.finish(), 4);
+
+    let script = "set -e\nfalse\nLIBRARY_AFTER_ERREXIT=ran\n";
+    let mut shell = Shell::new();
+    assert_eq!(shell.run_script(&mut script.as_bytes(), "script"), 1);
+    assert!(shell.exit_requested());
+    assert!(env::var("LIBRARY_AFTER_ERREXIT").is_err());
+}
+
+#[test]
+fn exit_trap_runs_on_finish() {
+    let mut shell = Shell::new();
+    shell.run_line("trap 'LIBRARY_TRAPPED=yes; exit 6' EXIT");
+    assert_eq!(shell.run_line("exit 2"), 2);
+    assert_eq!(shell.finish(), 6);
+    assert_eq!(env::var("LIBRARY_TRAPPED").as_deref(), Ok("yes"));
+}
+
+#[test]
+fn default_shell_knows_its_pid() {
+    assert_eq!(Shell::default().pid, std::process::id() as i32);
+}
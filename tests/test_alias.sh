@@ -0,0 +1,70 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$("$shell" 2>&1 <<'EOF_INPUT'
+alias greet='echo hello'
+greet world
+alias echo='echo [aliased]'
+echo loops once
+greet again
+alias shout='tr a-z A-Z'
+echo piped | shout
+alias both='echo one | tr a-z A-Z'
+both
+echo greet is an argument
+'greet' quoted
+alias quote="echo it's"
+alias
+alias greet
+unalias echo greet
+greet
+unalias greet
+alias bad/name=x
+EOF_INPUT
+)
+
+output=$(sed -E 's/^((mini-shell\([^)]*\)|)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "hello world"
+check "[aliased] loops once"
+check "[aliased] hello again"
+check "[ALIASED] PIPED"
+check "[ALIASED] ONE"
+check "[aliased] greet is an argument"
+check "mini-shell: greet: command not found"
+check "alias echo='echo [aliased]'"
+check "alias greet='echo hello'"
+check "alias quote='echo it'\''s'"
+check "alias shout='tr a-z A-Z'"
+check "unalias: greet: not found"
+check "alias: \`bad/name=x': invalid alias name"
+
+# An alias's value is read as part of the line, so operators in it separate
+# commands, and the command after each one gets its own alias expanded.
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/sub"
+output=$(cd "$dir/sub" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+alias up='cd .. && pwd'
+up
+alias two='echo first; echo second'
+two
+alias loud='tr a-z A-Z'
+alias chain='echo chained || false; echo quiet | loud'
+chain
+alias false_then='false || echo recovered'
+false_then
+EOF_INPUT
+)
+
+check "$dir"
+check "first"
+check "second"
+check "chained"
+check "QUIET"
+check "recovered"
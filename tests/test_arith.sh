@@ -0,0 +1,43 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$("$shell" 2>&1 <<'EOF_INPUT'
+echo $((2 + 3 * 4))
+echo $(( (2 + 3) * 4 ))
+echo $((10 - 4 - 3))
+echo $((17 / 5)) $((17 % 5))
+echo $((-3 + 1)) $((- -2)) $((2 * -3))
+echo $((1+2*3-4/2))
+echo "$((  7   *   6  ))"
+x=5
+echo $((x + 1)) $(($x * 2))
+echo $((unset_var + 1))
+echo "sum: $((x + x))!"
+echo $((1 / 0))
+echo "division status: $?"
+echo $((2 +))
+echo $(((1 + 2) * (3 + 4)))
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "14"
+check "20"
+check "3"
+check "3 2"
+check "-2 2 -6"
+check "5"
+check "42"
+check "6 10"
+check "1"
+check "sum: 10!"
+check "mini-shell: 1 / 0: division by 0"
+grep -q "^division status: [1-9]" <<< "$output" && echo "✅ Passed: division by zero fails" || { echo "❌ Failed: division by zero fails"; exit 1; }
+check "mini-shell: 2 +: syntax error in expression"
+check "21"
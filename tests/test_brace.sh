@@ -0,0 +1,72 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo "lists:" file{1,2,3}.txt
+echo "nested:" {a,b{c,d}}
+echo "adjacent:" {a,b}{1,2}
+echo "repeated:" pre{a,b}mid{1,2}
+echo "empty:" x{,y}
+echo "ranges:" {1..5}
+echo "down:" {5..1}
+echo "step:" {1..10..3}
+echo "negative:" {-2..2}
+echo "padded:" {08..11}
+echo "letters:" {a..e}
+echo "letter step:" {z..t..2}
+echo "too many:" {-9223372036854775808..9223372036854775807}
+echo "huge:" {1..9223372036854775807}
+echo "huge product:" {1..1000}{1..1000}
+echo "huge nested:" {a,{1..60000},{1..60000}}
+echo {1..100000} | wc -w
+echo "big step:" {-9223372036854775808..9223372036854775807..-9223372036854775808}
+echo "literal:" {foo} {} {a..} {1..b} a{b,c
+echo "quoted:" "{x,y}" '{x,y}' \{x,y}
+echo "invalid first:" {foo}{a,b}
+echo "inner literal:" {a,{b}}
+name=value
+echo "variable:" {$name,other} ${name}{1,2}
+echo "substitution:" {a,$(echo sub)}
+echo "quoted item:" {a,"b c"}
+touch made{1..3}.txt
+ls made*.txt | tr '\n' ' '; echo
+assigned={1,2}
+echo "assignment: $assigned"
+EOF_INPUT
+)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "lists: file1.txt file2.txt file3.txt"
+check "nested: a bc bd"
+check "adjacent: a1 a2 b1 b2"
+check "repeated: preamid1 preamid2 prebmid1 prebmid2"
+check "empty: x xy"
+check "ranges: 1 2 3 4 5"
+check "down: 5 4 3 2 1"
+check "step: 1 4 7 10"
+check "negative: -2 -1 0 1 2"
+check "padded: 08 09 10 11"
+check "letters: a b c d e"
+check "letter step: z x v t"
+check "too many: {-9223372036854775808..9223372036854775807}"
+check "big step: -9223372036854775808 0"
+check "huge: {1..9223372036854775807}"
+check "huge product: {1..1000}{1..1000}"
+check "huge nested: {a,{1..60000},{1..60000}}"
+check "100000"
+check "literal: {foo} {} {a..} {1..b} a{b,c"
+check "quoted: {x,y} {x,y} {x,y}"
+check "invalid first: {foo}a {foo}b"
+check "inner literal: a {b}"
+check "variable: value other value1 value2"
+check "substitution: a sub"
+check "quoted item: a b c"
+check "made1.txt made2.txt made3.txt "
+check "assignment: {1,2}"
@@ -0,0 +1,85 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/sub"
+
+output=$(cd "$dir" && env -u OLDPWD HOME="$dir/sub" "$shell" 2>&1 <<'EOF_INPUT'
+cd -
+echo "cd - without OLDPWD: $?"
+cd sub; pwd
+cd ..; pwd
+cd -
+cd /nonexistent
+echo "cd failure: $?"
+cd; pwd
+echo -n no newline; echo " <-"
+echo -e "tab\there"
+export GREETING=hello
+sh -c 'echo child sees $GREETING'
+export FAREWELL=bye
+set
+export -n GREETING
+sh -c 'echo child now sees "[$GREETING]"'
+set
+unset FAREWELL
+echo "unset expands to [$FAREWELL]"
+sh -c 'echo child sees "[$FAREWELL]" after unset'
+unset 1st a=b
+echo "invalid unset: $?"
+unset
+echo "unset usage: $?"
+export =foo
+echo "invalid export: $?"
+export -n =x
+echo "invalid export -n: $?"
+export PATH=/nonexistent
+true && echo "true: $?"
+false || echo "false: $?"
+: ignored args; echo "colon: $?"
+exit 7
+EOF_INPUT
+)
+status=$?
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "cd: OLDPWD not set"
+check "cd - without OLDPWD: 1"
+check "$dir/sub"
+check "$dir"
+check "cd failure: 1"
+check "no newline <-"
+check "$(printf 'tab\there')"
+check "child sees hello"
+check "FAREWELL=bye"
+check "GREETING=hello"
+check "child now sees []"
+check "unset expands to []"
+check "child sees [] after unset"
+check "unset: '1st': not a valid identifier"
+check "unset: 'a=b': not a valid identifier"
+check "invalid unset: 1"
+check "unset: usage: unset name ..."
+check "unset usage: 2"
+check "export: '=foo': not a valid identifier"
+check "invalid export: 1"
+check "export: '=x': not a valid identifier"
+check "invalid export -n: 1"
+[ "$(grep -c "^GREETING=" <<< "$output")" -eq 1 ] && echo "✅ Passed: export -n removes the variable" || { echo "❌ Failed: export -n removes the variable"; exit 1; }
+# PATH is gone by then, so these can only be builtins.
+check "true: 0"
+check "false: 1"
+check "colon: 0"
+[ "$status" = 7 ] && echo "✅ Passed: exit 7" || { echo "❌ Failed: exit status $status"; exit 1; }
+
+# `cd -` only prints the old directory once it has changed to it.
+output=$(OLDPWD="$dir/gone" "$shell" -c 'cd -; echo "cd - to a missing directory: $?"' 2>/dev/null)
+check "cd - to a missing directory: 1"
+! grep -qF "$dir/gone" <<< "$output" && echo "✅ Passed: failed cd - prints nothing" || { echo "❌ Failed: failed cd - prints nothing"; exit 1; }
@@ -0,0 +1,62 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(cd "$(mktemp -d)" && pwd -P)
+trap 'rm -rf "$dir"' EXIT
+mkdir -p "$dir/real/sub"
+ln -s real/sub "$dir/link"
+
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+cd link
+echo "logical: $PWD"
+pwd -P
+cd ..
+echo "back up: $(pwd)"
+cd link
+cd -P ..
+echo "physical parent: $PWD"
+cd -L ../link
+cd -P .
+echo "resolved: $PWD"
+echo "old: $OLDPWD"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "logical: $dir/link"
+check "$dir/real/sub"
+check "back up: $dir"
+check "physical parent: $dir/real"
+check "resolved: $dir/real/sub"
+check "old: $dir/link"
+
+# `$CDPATH` is searched before the current directory, and the directory
+# found is printed.
+mkdir -p "$dir/projects/app" "$dir/work/app" "$dir/work/local"
+output=$(cd "$dir/work" && CDPATH="$dir/projects:" HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+cd app
+echo "found: $PWD"
+cd "$OLDPWD"
+cd local
+echo "fallback: $PWD"
+cd ../app
+echo "relative: $PWD"
+cd /
+cd real
+echo "status: $?"
+EOF_INPUT
+)
+
+check "$dir/projects/app"
+check "found: $dir/projects/app"
+check "fallback: $dir/work/local"
+check "relative: $dir/work/app"
+grep -qxF "$dir/work/local" <<< "$output" && { echo "❌ Failed: an empty CDPATH entry prints nothing"; exit 1; }
+echo "✅ Passed: an empty CDPATH entry prints nothing"
+check "status: 1"
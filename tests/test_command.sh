@@ -0,0 +1,23 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(printf 'one\ntwo\n' | HISTFILE=/dev/null "$shell" -c 'wc -l | tr -d " "' 2>&1)
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+check "2"
+
+output=$(HISTFILE=/dev/null "$shell" -c 'echo "args: $0 $1 $2"; exit 3' name first second 2>&1)
+status=$?
+check "args: name first second"
+[ "$status" -eq 3 ] && echo "✅ Passed: -c exits with the command's status" || { echo "❌ Failed: -c exited with $status"; exit 1; }
+
+output=$(HISTFILE=/dev/null "$shell" --command 'false
+echo "status: $?"' 2>&1)
+check "status: 1"
+
+output=$(HISTFILE=/dev/null "$shell" -c 2>&1)
+status=$?
+check "mini-shell: -c: option requires an argument"
+[ "$status" -eq 2 ] && echo "✅ Passed: -c without a command exits with 2" || { echo "❌ Failed: -c without a command exited with $status"; exit 1; }
@@ -0,0 +1,31 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(cd "$dir" && "$shell" 2>&1 <<'EOF_INPUT'
+# a full-line comment
+echo visible # trailing comment > created.txt
+echo "# not a comment" '# nor this'
+echo color=#fff a#b
+echo first;# echo second
+echo done # an apostrophe's fine here
+ls
+exit
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "visible"
+check "# not a comment # nor this"
+check "color=#fff a#b"
+check "first"
+check "done"
+[ ! -e "$dir/created.txt" ] && echo "✅ Passed: redirection inside a comment is ignored" || { echo "❌ Failed: comment redirect created a file"; exit 1; }
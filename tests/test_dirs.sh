@@ -0,0 +1,42 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir -p "$dir/a/b"
+
+output=$(cd "$dir" && HOME="$dir" HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+dirs
+pushd a
+pushd b
+echo "pushed to $PWD"
+pushd
+echo "swapped to $PWD"
+popd
+popd
+echo "popped to $PWD"
+popd
+echo "empty status: $?"
+pushd missing
+echo "missing status: $?"
+dirs
+EOF_INPUT
+)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "~"
+check "~/a ~"
+check "~/a/b ~/a ~"
+check "pushed to $dir/a/b"
+check "~/a ~/a/b ~"
+check "swapped to $dir/a"
+check "~/a/b ~"
+check "popped to $dir"
+check "popd: directory stack empty"
+check "empty status: 1"
+check "pushd: missing: No such file or directory"
+check "missing status: 1"
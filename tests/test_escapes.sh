@@ -0,0 +1,31 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(TEST_DIR=/home/tester "$shell" 2>&1 <<'EOF_INPUT'
+echo a\ b
+echo \$TEST_DIR
+echo "\$TEST_DIR is $TEST_DIR"
+echo \"quoted\" \'single\'
+echo "say \"hi\""
+echo 'back\slash' "keep\n"
+echo a\;b a\|b a\>b
+echo \*.toml
+exit
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "a b"
+check "\$TEST_DIR"
+check "\$TEST_DIR is /home/tester"
+check "\"quoted\" 'single'"
+check "say \"hi\""
+check "back\\slash keep\\n"
+check "a;b a|b a>b"
+check "*.toml"
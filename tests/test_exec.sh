@@ -0,0 +1,77 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+printf '#!/bin/sh\necho ran\n' > "$dir/not_executable.sh"
+mkdir "$dir/bin" "$dir/subdir"
+printf 'echo no shebang: $1\n' > "$dir/bin/plain_script"
+chmod +x "$dir/bin/plain_script"
+
+output=$(cd "$dir" && PATH="$dir/bin:$PATH" "$shell" 2>&1 <<'EOF_INPUT'
+no_such_command --flag
+echo "not found: $?"
+./missing.sh
+echo "missing path: $?"
+./not_executable.sh
+echo "not executable: $?"
+./subdir
+echo "directory: $?"
+./not_executable.sh/x
+echo "not a directory: $?"
+sh -c 'kill -KILL $$'
+echo "killed: $?"
+sh -c 'kill -TERM $$' | cat
+echo "terminated: $?"
+yes | head -1 > /dev/null
+echo "sigpipe: $?"
+plain_script from-path
+bin/plain_script relative
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "mini-shell: no_such_command: command not found"
+check "not found: 127"
+check "mini-shell: ./missing.sh: No such file or directory"
+check "missing path: 127"
+check "mini-shell: ./not_executable.sh: Permission denied"
+check "not executable: 126"
+check "mini-shell: ./subdir: is a directory"
+check "directory: 126"
+check "mini-shell: ./not_executable.sh/x: Not a directory"
+check "not a directory: 126"
+check "Killed by signal 9"
+check "killed: 137"
+check "Killed by signal 15"
+check "terminated: 0"
+check "sigpipe: 0"
+check "no shebang: from-path"
+check "no shebang: relative"
+grep -q "Killed by signal 13" <<< "$output" && { echo "❌ Failed: SIGPIPE is not reported"; exit 1; }
+echo "✅ Passed: SIGPIPE is not reported"
+
+# A NUL byte can't be passed to a program, so the command fails instead.
+output=$(printf '/bin/echo x\0y\necho "nul: $?"\ntrue | printf "%%s\\n" "p\0q"\necho "nul in pipeline: $?"\n' \
+    | HISTFILE=/dev/null "$shell" 2>&1)
+check "mini-shell: invalid argument containing NUL byte"
+check "nul: 1"
+check "nul in pipeline: 1"
+grep -q "panicked" <<< "$output" && { echo "❌ Failed: no panic"; exit 1; }
+echo "✅ Passed: no panic"
+
+# NUL bytes are dropped from command substitutions and from what `read`
+# reads, as in bash, since a variable can't hold one.
+output=$(HISTFILE=/dev/null "$shell" -c "X=\$(printf 'a\\0b'); echo \"substituted: \$X\"" 2>&1)
+check "substituted: ab"
+output=$(printf 'c\0d\n' | HISTFILE=/dev/null "$shell" -c 'read X; echo "read: $X"' 2>&1)
+check "read: cd"
+output=$(printf 'X=e\0f\necho "assigned: $?"\n' | HISTFILE=/dev/null "$shell" 2>&1)
+check "mini-shell: invalid argument containing NUL byte"
+check "assigned: 2"
@@ -0,0 +1,52 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(TEST_DIR=/home/tester "$shell" <<'EOF_INPUT'
+echo $TEST_DIR
+echo ${TEST_DIR}/bin
+echo $TEST_DIR/docs
+echo [$MINI_SHELL_UNDEFINED]
+echo [${MINI_SHELL_UNDEFINED}]
+echo price is $
+exit
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "/home/tester"
+check "/home/tester/bin"
+check "/home/tester/docs"
+check "[]"
+check "price is \$"
+
+# `$$` is the shell's process ID, in subshells too, and `$!` the last
+# background command's.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo "before: [$!]"
+sh -c 'echo "parent: $PPID"'
+echo "shell: $$"
+echo "braced: ${$}"
+echo "substitution: $(echo $$)"
+echo "pipeline: $$" | cat
+echo tmp.$$
+sleep 0 &
+echo "background: $!"
+EOF_INPUT
+)
+
+check "before: []"
+pid=$(sed -n 's/^parent: //p' <<< "$output")
+check "shell: $pid"
+check "braced: $pid"
+check "substitution: $pid"
+check "pipeline: $pid"
+check "tmp.$pid"
+job=$(sed -n 's/^\[1\] //p' <<< "$output")
+[ -n "$job" ] || { echo "❌ Failed: the background job is announced"; exit 1; }
+check "background: $job"
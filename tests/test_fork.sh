@@ -0,0 +1,55 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+# Forks only fail on demand for a user under a process limit, which takes
+# root to set up.
+if [ "$(id -u)" -ne 0 ] || ! command -v setpriv > /dev/null || ! command -v prlimit > /dev/null; then
+    echo "⏭️  Skipped: needs root, setpriv and prlimit"
+    exit 0
+fi
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+chmod 755 "$dir"
+cp "$shell" "$dir/mini-shell"
+
+# With a limit of one process the shell itself is the only one its user can
+# have, so every fork fails.
+output=$(cd "$dir" && setpriv --reuid=65534 --regid=65534 --clear-groups prlimit --nproc=1 -- \
+    env HISTFILE=/dev/null ./mini-shell 2>&1 <<'EOF_INPUT'
+echo before
+/bin/true
+echo "external: $?"
+/bin/true | cat
+echo "pipeline: $?"
+echo "substitution: [$(echo inner)]"
+echo after
+EOF_INPUT
+)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "before"
+check "mini-shell: /bin/true: fork failed: Try again"
+check "external: 1"
+check "pipeline: 1"
+check "mini-shell: \$(echo inner): fork failed: Try again"
+check "substitution: []"
+check "after"
+
+# Room for one more process: the first stage of a pipeline starts and is
+# stopped again when the second can't.
+limit=$(( $(ps -L -u 65534 --no-headers | wc -l) + 2 ))
+start=$(date +%s)
+output=$(cd "$dir" && setpriv --reuid=65534 --regid=65534 --clear-groups prlimit --nproc=$limit -- \
+    env HISTFILE=/dev/null ./mini-shell 2>&1 <<'EOF_INPUT'
+/bin/sleep 5 | /bin/cat
+echo "partial pipeline: $?"
+EOF_INPUT
+)
+check "mini-shell: /bin/cat: fork failed: Try again"
+check "partial pipeline: 1"
+[ $(( $(date +%s) - start )) -lt 4 ] && echo "✅ Passed: the stage that started is stopped" || { echo "❌ Failed: the stage that started is stopped"; exit 1; }
@@ -0,0 +1,34 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/sub"
+touch "$dir/a.rs" "$dir/b.rs" "$dir/c.txt" "$dir/ab.md" "$dir/.hidden.rs" "$dir/sub/d.rs"
+
+output=$(cd "$dir" && "$shell" <<'EOF_INPUT'
+echo *.rs
+echo ?.txt
+echo [ab].rs
+echo [!a].rs
+echo sub/*.rs
+echo *.none
+echo .*.rs
+exit
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "a.rs b.rs"
+check "c.txt"
+check "a.rs b.rs"
+check "b.rs"
+check "sub/d.rs"
+check "*.none"
+check ".hidden.rs"
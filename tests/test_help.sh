@@ -0,0 +1,24 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+help
+help cd
+help nope
+echo "status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "cd [-L|-P] [-|dir]                   Change the working directory."
+check "help [name]                          Describe the builtins."
+check "cd: cd [-L|-P] [-|dir]"
+check "    Change the working directory."
+check "help: no help topics match 'nope'"
+check "status: 1"
@@ -0,0 +1,54 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(cd "$dir" && "$shell" 2>&1 <<'EOF_INPUT'
+NAME=world
+cat << EOF
+hello $NAME
+it's "quoted": $(echo substituted) \$NAME
+# not a comment
+EOF
+cat << 'EOF'
+raw $NAME
+EOF
+cat <<"EOF"
+also raw $(echo no)
+EOF
+cat <<-END | tr a-z A-Z
+		tabbed $NAME
+	END
+cat <<ONE; cat <<TWO
+from one
+ONE
+from two
+TWO
+wc -l << EOF > count.txt
+a
+b
+c
+EOF
+cat count.txt
+echo after
+EOF_INPUT
+)
+
+output=$(sed -E 's/^((mini-shell\([^)]*\)|)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "hello world"
+check "it's \"quoted\": substituted \$NAME"
+check "# not a comment"
+check "raw \$NAME"
+check "also raw \$(echo no)"
+check "TABBED WORLD"
+check "from one"
+check "from two"
+check "3"
+check "after"
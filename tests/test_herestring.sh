@@ -0,0 +1,26 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$("$shell" 2>&1 <<'EOF_INPUT'
+text="foo bar"
+grep foo <<< "$text"
+od -c <<< hi | head -1
+wc -c <<< 'two words'
+cat <<<unquoted$text
+tr a-z A-Z <<< "$(echo substituted)" | cat
+EOF_INPUT
+)
+
+output=$(sed -E 's/^((mini-shell\([^)]*\)|)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "foo bar"
+# The string arrives with a trailing newline, as in bash.
+check "0000000   h   i  \n"
+check "10"
+check "unquotedfoo bar"
+check "SUBSTITUTED"
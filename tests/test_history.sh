@@ -0,0 +1,103 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+export HISTFILE="$dir/history"
+
+"$shell" > /dev/null 2>&1 <<'EOF_INPUT'
+echo one
+echo one
+
+echo two
+EOF_INPUT
+
+output=$("$shell" 2>&1 <<'EOF_INPUT'
+echo three
+history
+exit
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "    1  echo one"
+check "    2  echo two"
+check "    3  echo three"
+check "    4  history"
+# one, two, three, history and exit: the repeat and the blank line are skipped.
+[ "$(wc -l < "$HISTFILE")" -eq 5 ] && echo "✅ Passed: exit saves the history file" || { echo "❌ Failed: exit saves the history file"; exit 1; }
+
+# History expansion: `!!` is the previous command and `!n` is entry n.
+output=$(HISTFILE="$dir/events" "$shell" 2>&1 <<'EOF_INPUT'
+!!
+echo first
+!!
+echo 'a!!b' \!!
+!1
+!9
+history
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check "mini-shell: !!: event not found"
+check "echo first"
+check "a!!b !!"
+check "mini-shell: !9: event not found"
+check "    3  echo first"
+[ "$(grep -cxF "first" <<< "$output")" -eq 3 ] && echo "✅ Passed: events run the expanded command" || { echo "❌ Failed: events run the expanded command"; exit 1; }
+
+# HISTCONTROL: repeats are only skipped by default, so an empty one keeps them.
+output=$(HISTFILE="$dir/control" HISTCONTROL= "$shell" 2>&1 <<'EOF_INPUT'
+echo same
+echo same
+ echo spaced
+history
+EOF_INPUT
+)
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+check "    2  echo same"
+check "    3   echo spaced"
+
+output=$(HISTFILE="$dir/ignoreboth" HISTCONTROL=ignoreboth "$shell" 2>&1 <<'EOF_INPUT'
+echo same
+echo same
+ echo spaced
+history
+EOF_INPUT
+)
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+check "    1  echo same"
+check "    2  history"
+grep -qF "echo spaced" <<< "$output" && { echo "❌ Failed: ignorespace"; exit 1; }
+echo "✅ Passed: ignorespace"
+
+# HISTSIZE keeps the newest entries, including those loaded from the file.
+printf 'echo old%s\n' 1 2 3 4 5 > "$dir/sized"
+output=$(HISTFILE="$dir/sized" HISTSIZE=3 "$shell" 2>&1 <<'EOF_INPUT'
+history
+echo new
+history
+EOF_INPUT
+)
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+check "    1  echo old4"
+check "    2  echo new"
+check "    3  history"
+grep -qF "echo old3" <<< "$output" && { echo "❌ Failed: HISTSIZE trims old entries"; exit 1; }
+echo "✅ Passed: HISTSIZE trims old entries"
+[ "$(wc -l < "$dir/sized")" -eq 3 ] && echo "✅ Passed: the saved file is trimmed to HISTSIZE" || { echo "❌ Failed: the saved file is trimmed to HISTSIZE"; exit 1; }
+
+# HISTFILESIZE trims the saved file on its own.
+printf 'echo old%s\n' 1 2 3 4 5 > "$dir/filesized"
+HISTFILE="$dir/filesized" HISTFILESIZE=2 "$shell" > /dev/null 2>&1 <<'EOF_INPUT'
+echo new
+EOF_INPUT
+[ "$(cat "$dir/filesized")" = "$(printf 'echo old5\necho new')" ] && echo "✅ Passed: HISTFILESIZE trims the saved file" || { echo "❌ Failed: HISTFILESIZE trims the saved file"; exit 1; }
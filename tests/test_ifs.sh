@@ -0,0 +1,47 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+FILES="a b  c"
+touch $FILES
+ls
+printf "[%s]\n" "$FILES"
+printf "<%s>" x$FILES; echo
+printf "<%s>" $EMPTY end; echo
+printf "<%s>" "$EMPTY" end; echo
+COPY=$FILES
+printf "{%s}\n" "$COPY"
+IFS=:
+P="one:two::three"
+printf "<%s>" $P; echo
+printf "<%s>" $FILES; echo
+IFS=" :"
+Q=" x : y "
+printf "<%s>" $Q; echo
+IFS=
+printf "<%s>" $FILES; echo
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+for file in a b c; do
+    [ -f "$dir/$file" ] && echo "✅ Passed: created $file" || { echo "❌ Failed: created $file"; exit 1; }
+done
+check "[a b  c]"
+check "<xa><b><c>"
+check "<end>"
+check "<><end>"
+check "{a b  c}"
+check "<one><two><><three>"
+# Neither `:` nor an empty IFS splits on spaces.
+[ "$(grep -cxF "<a b  c>" <<< "$output")" -eq 2 ] && echo "✅ Passed: IFS without spaces" || { echo "❌ Failed: IFS without spaces"; exit 1; }
+check "<x><y>"
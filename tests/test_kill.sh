@@ -0,0 +1,50 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sleep 100 &
+kill %1
+sleep 0.2
+sleep 100 &
+kill -KILL %1
+sleep 0.2
+sleep 100 &
+kill -usr1 %1
+sleep 0.2
+sleep 100 &
+kill -SIGHUP %1
+sleep 0.2
+kill -BOGUS 1
+echo "bogus status: $?"
+kill %7
+echo "no job status: $?"
+kill -TERM 999999
+echo "no pid status: $?"
+kill abc
+kill
+echo "usage status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+# SIGTERM, then SIGKILL, ends the background jobs.
+check "[1]+  Exit 143  sleep 100"
+check "[1]+  Exit 137  sleep 100"
+# Signal names work in either case and with or without the SIG prefix.
+check "[1]+  Exit 138  sleep 100"
+check "[1]+  Exit 129  sleep 100"
+check "kill: BOGUS: invalid signal specification"
+check "bogus status: 1"
+check "kill: %7: no such job"
+check "no job status: 1"
+check "kill: (999999) - No such process"
+check "no pid status: 1"
+check "kill: abc: arguments must be process or job IDs"
+check "kill: usage: kill [-sig] pid|%job ..."
+check "usage status: 2"
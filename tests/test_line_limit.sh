@@ -0,0 +1,34 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+long=$(head -c 3000 /dev/zero | tr '\0' a)
+printf 'echo before\necho %s\necho "after: $?"\n' "$long" > "$dir/script.msh"
+printf 'read line\n%s\necho "read status: $?"\necho %s\n' "$long" "$(head -c 900 /dev/zero | tr '\0' b)" > "$dir/input.txt"
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+# An over-long line is thrown away with an error, and reading carries on.
+output=$(MINI_SHELL_MAX_LINE=1000 HISTFILE=/dev/null "$shell" < "$dir/script.msh" 2>&1)
+check "before"
+check "mini-shell: line too long"
+check "after: 1"
+grep -q "aaaa" <<< "$output" && { echo "❌ Failed: the long line doesn't run"; exit 1; }
+echo "✅ Passed: the long line doesn't run"
+
+output=$(MINI_SHELL_MAX_LINE=1000 HISTFILE=/dev/null "$shell" "$dir/script.msh" 2>&1)
+check "mini-shell: $dir/script.msh: line too long"
+check "after: 1"
+
+output=$(MINI_SHELL_MAX_LINE=1000 HISTFILE=/dev/null "$shell" < "$dir/input.txt" 2>&1)
+check "read: line too long"
+check "read status: 1"
+check "$(head -c 900 /dev/zero | tr '\0' b)"
+
+# The default limit is a few megabytes.
+output=$(printf 'echo %s\n' "$long" | HISTFILE=/dev/null "$shell" 2>&1)
+check "$long"
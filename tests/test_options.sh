@@ -0,0 +1,86 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+# xtrace prints each command to stderr after expansion.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 >/dev/null <<'EOF_INPUT'
+name="two words"
+set -x
+echo $name "$name" it\'s ''
+GREETING=hi printf '%s\n' done
+set +x
+echo untraced
+EOF_INPUT
+)
+check "+ echo two words 'two words' 'it'\''s' ''"
+check "+ GREETING=hi printf '%s\n' done"
+check "+ set +x"
+grep -qF "untraced" <<< "$output" && { echo "❌ Failed: set +x stops tracing"; exit 1; }
+echo "✅ Passed: set +x stops tracing"
+
+# nounset makes an unset variable an error that ends a script, with status 2.
+output=$(env -u NO_SUCH_VAR HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+set -u
+echo "[$@] $#"
+echo "[$NO_SUCH_VAR]"
+echo "not reached"
+EOF_INPUT
+)
+status=$?
+check "[] 0"
+check "mini-shell: NO_SUCH_VAR: unbound variable"
+grep -qxF "not reached" <<< "$output" && { echo "❌ Failed: nounset stops the script"; exit 1; }
+[ "$status" -eq 2 ] && echo "✅ Passed: nounset stops the script" || { echo "❌ Failed: nounset stops the script"; exit 1; }
+
+output=$(env -u NO_SUCH_VAR HISTFILE=/dev/null "$shell" -c 'set -u; echo "[${NO_SUCH_VAR}]" || echo "not reached"' 2>&1)
+check "mini-shell: NO_SUCH_VAR: unbound variable"
+grep -qxF "not reached" <<< "$output" && { echo "❌ Failed: nounset isn't a tested failure"; exit 1; }
+echo "✅ Passed: nounset isn't a tested failure"
+output=$(HISTFILE=/dev/null "$shell" -c 'set -u; echo hidden > "$3"; echo "not reached"' 2>&1)
+check "mini-shell: 3: unbound variable"
+grep -qxF "not reached" <<< "$output" && { echo "❌ Failed: nounset in a redirection"; exit 1; }
+echo "✅ Passed: nounset in a redirection"
+
+output=$(env -u NO_SUCH_VAR HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+set -u
+set +u
+echo "off: [$NO_SUCH_VAR]"
+EOF_INPUT
+)
+check "off: []"
+
+# errexit ends the shell on a failure that isn't being tested.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+set -e
+false && echo "not run"
+false || echo "tested failure"
+true | false && echo "not run"
+echo "still going"
+sh -c 'exit 3'
+echo "not reached"
+EOF_INPUT
+)
+status=$?
+check "tested failure"
+check "still going"
+grep -qxF "not reached" <<< "$output" && { echo "❌ Failed: set -e stops at a failure"; exit 1; }
+echo "✅ Passed: set -e stops at a failure"
+[ "$status" -eq 3 ] && echo "✅ Passed: set -e exits with the failed status" || { echo "❌ Failed: set -e exited with $status"; exit 1; }
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+set -eux
+set +eux
+set -o
+set -q
+echo "bad option: $?"
+EOF_INPUT
+)
+check "errexit         off"
+check "nounset         off"
+check "xtrace          off"
+check "set: -q: invalid option"
+check "bad option: 2"
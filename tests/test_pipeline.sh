@@ -0,0 +1,113 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+# A stray pipe end left open in any stage would keep `yes` writing forever.
+output=$(cd "$dir" && timeout 5 "$shell" 2>&1 <<'EOF_INPUT'
+yes | head -1
+yes | cat | head -n 2 | wc -l
+echo hello | tr a-z A-Z > upper.txt
+cat < upper.txt | wc -c
+ls /proc/self/fd > fds.txt
+wc -l < fds.txt
+EOF_INPUT
+)
+status=$?
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+[ "$status" -ne 124 ] && echo "✅ Passed: pipelines terminate" || { echo "❌ Failed: pipelines terminate"; exit 1; }
+check "y"
+check "2"
+check "6"
+# Only stdin, stdout, stderr and the fd `ls` opens to read the directory.
+check "4"
+
+# Pipelines and `&&`/`||` lists can run in the background as one job.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo one | tr a-z A-Z &
+sleep 0.2
+true && echo two && echo three &
+sleep 0.2
+false || echo four; echo five
+false && echo skipped || echo six
+echo a && && echo b
+echo "and status: $?"
+| echo c
+echo "pipe status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check "ONE"
+check "[1]+  Done      echo one | tr a-z A-Z"
+check "two"
+check "three"
+check "[1]+  Done      true && echo two && echo three"
+check "four"
+check "five"
+check "six"
+grep -qxF "skipped" <<< "$output" && { echo "❌ Failed: && after a failure is skipped"; exit 1; }
+echo "✅ Passed: && after a failure is skipped"
+check "mini-shell: syntax error near unexpected token \`&&'"
+check "and status: 2"
+check "mini-shell: syntax error near unexpected token \`|'"
+check "pipe status: 2"
+
+# Builtins run inside their pipeline stage, and can't change the shell.
+dir=$(cd "$dir" && pwd -P)
+mkdir "$dir/real"
+ln -s real "$dir/link"
+output=$(cd "$dir/link" && PWD="$dir/link" HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+pwd | cat
+type cd | tr a-z A-Z
+echo -n no newline | tr a-z A-Z; echo
+cd / | cat
+echo "still in $PWD"
+exit 4 | cat
+echo "after exit: $?"
+EOF_INPUT
+)
+
+check "$dir/link"
+check "CD IS A SHELL BUILTIN"
+check "NO NEWLINE"
+check "still in $dir/link"
+check "after exit: 0"
+
+# `&` in the middle of a line ends a background command, and a builtin in
+# the background runs apart from the shell.
+start=$(date +%s%N)
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sh -c 'sleep 0.3; echo late' & echo "right away"
+wait
+timeout 2 sleep 0.5 &
+echo "timeout job: [$!]"
+cd / &
+wait
+echo "cwd: $PWD"
+true && echo listed & wait
+& echo nothing
+echo "leading ampersand: $?"
+EOF_INPUT
+)
+elapsed=$(( ($(date +%s%N) - start) / 1000000 ))
+
+check "right away"
+check "late"
+[ "$(grep -nxF 'right away' <<< "$output" | cut -d: -f1)" -lt "$(grep -nxF 'late' <<< "$output" | cut -d: -f1)" ] \
+    && echo "✅ Passed: the command after & doesn't wait" || { echo "❌ Failed: the command after & doesn't wait"; exit 1; }
+grep -qE '^timeout job: \[[0-9]+\]$' <<< "$output" && echo "✅ Passed: a background builtin sets \$!" || { echo "❌ Failed: a background builtin sets \$!"; exit 1; }
+check "cwd: $dir"
+check "listed"
+check "mini-shell: syntax error near unexpected token \`&'"
+check "leading ampersand: 2"
+[ "$elapsed" -lt 1500 ] && echo "✅ Passed: background builtins don't block" || { echo "❌ Failed: background builtins took ${elapsed}ms"; exit 1; }
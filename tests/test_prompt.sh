@@ -0,0 +1,54 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+user=$(id -un)
+host=$(hostname | cut -d. -f1)
+
+# Prompts only show on a terminal, so `script` provides one; with TERM=dumb
+# the line editor prints the bare prompt. The first line of output is the
+# prompt, followed by the echo of the `exit` typed once it's up.
+session() {
+    (sleep 0.3; printf '%s\n' "$@"; echo exit) |
+        (cd "$dir" && TERM=dumb NO_COLOR=1 SHELL=/bin/sh script -qec "$ps1 '$shell'" /dev/null) | tr -d '\r'
+}
+
+run() {
+    ps1="env PS1='$1'" session | head -1 | sed 's/exit$//'
+}
+
+check() {
+    [ "$1" = "$2" ] && echo "✅ Passed: $3" || { echo "❌ Failed: $3 (got '$1', wanted '$2')"; exit 1; }
+}
+
+check "$(run '\w> ')" "$dir> " '\w'
+check "$(run '\u> ')" "$user> " '\u'
+check "$(run '\h> ')" "$host> " '\h'
+check "$(run '\$ ')" '$ ' '\$'
+check "$(run 'a\\b ')" 'a\b ' '\\'
+check "$(run '\q\w')" "\\q$dir" 'unknown escapes stay literal'
+check "$(ps1="env -u PS1" session | head -1 | sed 's/exit$//')" "mini-shell($dir)> " 'default prompt'
+
+output=$(ps1="env -u PS1" session "export PS1='custom\$ '" "echo next")
+grep -qxF 'custom$ next' <<< "$output" && echo "✅ Passed: PS1 set in the session" || { echo "❌ Failed: PS1 set in the session"; exit 1; }
+
+# A working directory removed from under the shell shows as `?`.
+output=$(ps1="env -u PS1" session "mkdir gone; cd gone; rmdir ../gone" "echo still running")
+grep -qF 'mini-shell(?)> ' <<< "$output" && echo "✅ Passed: deleted working directory" || { echo "❌ Failed: deleted working directory"; exit 1; }
+
+# Without a terminal there's no prompt, so stdout only has command output.
+output=$(cd "$dir" && "$shell" 2> /dev/null <<< "echo only this")
+[ "$output" = "only this" ] && echo "✅ Passed: no prompt without a terminal" || { echo "❌ Failed: no prompt without a terminal (got '$output')"; exit 1; }
+
+# A failed command's status shows in the usual prompt until one succeeds.
+# Input is typed ahead, so prompts and echoed commands can run together.
+output=$(ps1="env -u PS1" session "false" "sh -c 'exit 3'" "true")
+grep -qF "mini-shell($dir)[1]> " <<< "$output" && echo "✅ Passed: status after a failure" || { echo "❌ Failed: status after a failure"; exit 1; }
+grep -qF "mini-shell($dir)[3]> " <<< "$output" && echo "✅ Passed: status of the last command" || { echo "❌ Failed: status of the last command"; exit 1; }
+[ "$(grep -oF "mini-shell($dir)> " <<< "$output" | wc -l)" -eq 2 ] && echo "✅ Passed: no status after a success" || { echo "❌ Failed: no status after a success"; exit 1; }
+
+output=$(ps1="env -u PS1 MINISHELL_SHOW_STATUS=0" session "false")
+grep -qF "[1]" <<< "$output" && { echo "❌ Failed: MINISHELL_SHOW_STATUS=0"; exit 1; }
+echo "✅ Passed: MINISHELL_SHOW_STATUS=0"
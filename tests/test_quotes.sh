@@ -0,0 +1,30 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(TEST_DIR=/home/tester "$shell" 2>&1 <<'EOF_INPUT'
+echo "hello   world"
+echo 'a   b   c'
+echo x "" y
+echo a"b c"d
+echo '$TEST_DIR' "$TEST_DIR"
+echo "a > b" 'c | d'
+echo still alive
+echo "not closed
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "hello   world"
+check "a   b   c"
+check "x  y"
+check "ab cd"
+check "\$TEST_DIR /home/tester"
+check "a > b c | d"
+check "still alive"
+check "mini-shell: syntax error: unexpected EOF while looking for matching \`\"'"
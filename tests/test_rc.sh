@@ -0,0 +1,43 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+cat > "$dir/.mini_shellrc" <<'EOF_RC'
+alias greet='echo hello from rc'
+export RC_LOADED=default
+no_such_command_in_rc
+EOF_RC
+cat > "$dir/other.rc" <<'EOF_RC'
+export RC_LOADED=other
+EOF_RC
+printf 'echo "script sees: [$RC_LOADED]"\n' > "$dir/script.msh"
+
+run() {
+    (cd "$dir" && env -u RC_LOADED HOME="$dir" "$shell" "$@" 2>&1) | sed -E 's/^(mini-shell\([^)]*\)> )+//'
+}
+
+output=$(run <<< 'greet; echo "loaded: $RC_LOADED"')
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+check "mini-shell: no_such_command_in_rc: command not found"
+check "hello from rc"
+check "loaded: default"
+
+output=$(run --norc <<< 'echo "loaded: [$RC_LOADED]"')
+check "loaded: []"
+
+output=$(run --rcfile other.rc <<< 'echo "loaded: $RC_LOADED"')
+check "loaded: other"
+
+output=$(run --rcfile missing.rc <<< 'echo still starts')
+check "source: missing.rc: No such file or directory"
+check "still starts"
+
+output=$(run script.msh)
+check "script sees: []"
+
+output=$(run --rcfile other.rc script.msh)
+check "script sees: [other]"
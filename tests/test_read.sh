@@ -0,0 +1,48 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+read a b
+  one two  three  
+echo "[$a] [$b]"
+read -p "name> " n
+bob
+echo "hello $n"
+read x y z
+solo
+echo "[$x][$y][$z]"
+read 1bad
+echo "invalid status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "[one] [two  three]"
+check "hello bob"
+# Like the shell's own, the prompt is only for input from a terminal.
+grep -q "name>" <<< "$output" && { echo "❌ Failed: read -p without a terminal"; exit 1; }
+echo "✅ Passed: read -p without a terminal"
+check "[solo][][]"
+check "read: \`1bad': not a valid identifier"
+check "invalid status: 1"
+
+# A script reads its commands from the file, so `read` gets stdin.
+cat > "$dir/script" <<'EOF_INPUT'
+read line
+echo "got $line: $?"
+read line
+echo "eof status: $?"
+EOF_INPUT
+output=$(echo "piped" | "$shell" "$dir/script" 2>&1)
+
+check "got piped: 0"
+check "eof status: 1"
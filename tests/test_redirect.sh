@@ -0,0 +1,273 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+echo "old contents" > "$dir/existing.txt"
+
+output=$(cd "$dir" && "$shell" 2>&1 <<'EOF_INPUT'
+> created.txt
+echo "create status: $?"
+> existing.txt
+wc -c < existing.txt
+< existing.txt
+echo "read status: $?"
+< missing.txt
+echo "missing status: $?"
+   
+echo "blank line: $?"
+printf "first\n" > out.txt
+printf "second\n" >> out.txt
+cat < out.txt
+> stage.txt | cat
+echo "pipeline status: $?"
+sh -c 'echo to out; echo to err >&2' > both.txt 2>&1
+sh -c 'echo to out; echo to err >&2' 2>&1 > only_out.txt
+sh -c 'echo piped err >&2' 2>&1 | tr a-z A-Z
+sh -c 'echo dup to err' 2> /dev/null >&2
+sh -c 'echo bad fd' >&7
+printf "b\na\n" > "my file.txt"
+sort < 'my file.txt'
+printf "joined\n">glued.txt
+cat<glued.txt
+printf "escaped\n" > spaced\ name.txt
+cat "spaced name.txt"
+printf "%s%s%s\n" "quoted op" ">" x
+name="var file.txt"
+printf "from var\n" > "$name"
+cat "var file.txt"
+printf "x\n" > *.txt
+echo "ambiguous status: $?"
+printf "x\n" >
+echo "no target status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+[ -f "$dir/created.txt" ] && echo "✅ Passed: > file creates the file" || { echo "❌ Failed: > file creates the file"; exit 1; }
+check "create status: 0"
+check "0"
+check "read status: 0"
+check "mini-shell: missing.txt: No such file or directory"
+check "missing status: 1"
+check "blank line: 0"
+check "first"
+check "second"
+check "pipeline status: 0"
+
+# `> file 2>&1` sends both streams to the file; `2>&1 > file` only stdout.
+[ "$(cat "$dir/both.txt")" = "$(printf 'to out\nto err')" ] && echo "✅ Passed: > file 2>&1" || { echo "❌ Failed: > file 2>&1"; exit 1; }
+[ "$(cat "$dir/only_out.txt")" = "to out" ] && echo "✅ Passed: 2>&1 > file" || { echo "❌ Failed: 2>&1 > file"; exit 1; }
+check "to err"
+check "PIPED ERR"
+grep -qxF "dup to err" <<< "$output" && { echo "❌ Failed: 2> /dev/null >&2"; exit 1; }
+echo "✅ Passed: 2> /dev/null >&2"
+check "mini-shell: 7: Bad file number"
+[ -f "$dir/my file.txt" ] && echo "✅ Passed: quoted target with a space" || { echo "❌ Failed: quoted target with a space"; exit 1; }
+check "a"
+check "b"
+check "joined"
+check "escaped"
+check "quoted op>x"
+check "from var"
+check 'mini-shell: *.txt: ambiguous redirect'
+check "ambiguous status: 1"
+check "mini-shell: syntax error: missing redirection target"
+check "no target status: 2"
+
+# With noclobber, `>` leaves existing regular files alone and `>|` doesn't.
+output=$(cd "$dir" && "$shell" 2>&1 <<'EOF_INPUT'
+printf "first\n" > kept.txt
+printf "overwritten\n" > kept.txt
+set -o noclobber
+printf "refused\n" > kept.txt
+echo "noclobber status: $?"
+cat kept.txt
+printf "forced\n" >| kept.txt
+cat kept.txt
+printf "fresh\n" > fresh.txt
+cat fresh.txt
+printf "appended\n" >> kept.txt
+printf "discarded\n" > /dev/null
+echo "device status: $?"
+set -o
+set +C
+printf "clobbered\n" > kept.txt
+cat kept.txt
+set -o
+EOF_INPUT
+)
+
+check "overwritten"
+check "mini-shell: kept.txt: cannot overwrite existing file"
+check "noclobber status: 1"
+grep -qxF "refused" <<< "$output" && { echo "❌ Failed: noclobber keeps the file"; exit 1; }
+echo "✅ Passed: noclobber keeps the file"
+check "forced"
+check "fresh"
+check "device status: 0"
+check "noclobber       on"
+check "clobbered"
+check "noclobber       off"
+
+# Builtins run in the shell, with their redirections undone afterwards.
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo hi > builtin.txt
+echo -n more >> builtin.txt
+pwd > pwd.txt
+type echo > type.txt
+history > history.txt
+cd /nonexistent 2> cd_error.txt
+echo "cd status: $?"
+echo lost > /nonexistent/file
+echo "bad target: $?"
+echo to stdout
+read line < builtin.txt
+echo "read: $line"
+echo quiet 2> /dev/null >&2
+EOF_INPUT
+)
+
+check "to stdout"
+check "cd status: 1"
+check "mini-shell: /nonexistent/file: No such file or directory"
+check "bad target: 1"
+check "read: hi"
+grep -qF "quiet" <<< "$output" && { echo "❌ Failed: builtin stderr redirection"; exit 1; }
+echo "✅ Passed: builtin stderr redirection"
+[ "$(cat "$dir/builtin.txt")" = "$(printf 'hi\nmore')" ] && echo "✅ Passed: echo hi > f" || { echo "❌ Failed: echo hi > f"; exit 1; }
+[ "$(cat "$dir/pwd.txt")" = "$dir" ] && echo "✅ Passed: pwd > f" || { echo "❌ Failed: pwd > f"; exit 1; }
+[ "$(cat "$dir/type.txt")" = "echo is a shell builtin" ] && echo "✅ Passed: type > f" || { echo "❌ Failed: type > f"; exit 1; }
+grep -qF "history > history.txt" "$dir/history.txt" && echo "✅ Passed: history > f" || { echo "❌ Failed: history > f"; exit 1; }
+grep -qF "No such file or directory" "$dir/cd_error.txt" && echo "✅ Passed: cd 2> f" || { echo "❌ Failed: cd 2> f"; exit 1; }
+
+# Operators end the words around them, so spaces aren't needed.
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+printf 'nospace\n'>tight.txt
+cat<tight.txt
+printf 'more\n'>>tight.txt;cat tight.txt
+printf 'first\n'>one.txt>two.txt
+echo "one: $(cat one.txt)"
+cat two.txt
+sh -c 'echo both >&2' 2>&1>combined.txt | tr a-z A-Z
+sh -c 'echo swapped >&2' 2>&1>/dev/null|tr a-z A-Z
+echo "combined: $(cat combined.txt)"
+cat<<<tight
+EOF_INPUT
+)
+
+check "nospace"
+check "more"
+check "one: "
+check "first"
+check "BOTH"
+check "SWAPPED"
+check "combined: "
+check "tight"
+
+# `&>` and `&>>` send stdout and stderr to the same file.
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sh -c 'echo out; echo err >&2' &> both.log
+sh -c 'echo out2; echo err2 >&2' &>> both.log
+echo "both: $(cat both.log | tr '\n' ' ')"
+sh -c 'echo bg; echo bgerr >&2' &>background.log &
+wait
+echo "background: $(cat background.log | tr '\n' ' ')"
+echo builtin&>builtin.log
+echo "builtin: $(cat builtin.log)"
+true && sh -c 'echo listed >&2' &> list.log && echo "list: $(cat list.log)"
+EOF_INPUT
+)
+
+check "both: out err out2 err2 "
+check "background: bg bgerr "
+check "builtin: builtin"
+check "list: listed"
+grep -qE "^(out|err|bg|bgerr|listed)$" <<< "$output" && { echo "❌ Failed: nothing escapes &>"; exit 1; }
+echo "✅ Passed: nothing escapes &>"
+
+# Descriptors past 2, and `exec` keeping redirections for the whole shell.
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sh -c 'echo swapped >&2' 3>&1 1>/dev/null 2>&3 | tr a-z A-Z
+printf 'hidden\n' 3>&1 1>/dev/null
+exec 3> fd.log
+echo "from the shell" >&3
+sh -c 'echo "from a child" >&3'
+exec 3>&-
+echo gone >&3
+echo "closed: $?"
+cat fd.log
+exec 12< fd.log
+read line <&12
+echo "fd 12: $line"
+echo high 10> fd10.txt > /dev/null
+echo "after fd 10"
+echo leaked 99999> /dev/null
+echo "past the limit: $?"
+/bin/echo leaked 99999> /dev/null
+echo "program past the limit: $?"
+echo leaked 99999> /dev/null > unopened.txt
+[ -e unopened.txt ] || echo "later redirections skipped"
+echo leaked 99999999999> /dev/null
+echo "too big: $?"
+exec > exec_out.txt
+echo "into the file"
+exec 1>&2
+echo "back: $(cat exec_out.txt)"
+exec no_such_program
+echo "failed exec: $?"
+exec sh -c 'echo replaced; exit 4'
+echo "not reached"
+EOF_INPUT
+)
+status=$?
+
+check "SWAPPED"
+grep -qxF "hidden" <<< "$output" && { echo "❌ Failed: 1>/dev/null after 3>&1"; exit 1; }
+echo "✅ Passed: 1>/dev/null after 3>&1"
+check "from the shell"
+check "from a child"
+check "mini-shell: 3: Bad file number"
+check "closed: 1"
+check "fd 12: from the shell"
+check "after fd 10"
+check "mini-shell: 99999: Bad file number"
+check "past the limit: 1"
+check "program past the limit: 1"
+check "later redirections skipped"
+check "mini-shell: 99999999999: Bad file number"
+check "too big: 2"
+grep -qxF "leaked" <<< "$output" && { echo "❌ Failed: a bad descriptor redirects nothing"; exit 1; }
+echo "✅ Passed: a bad descriptor redirects nothing"
+check "back: into the file"
+check "exec: no_such_program: not found"
+check "failed exec: 127"
+check "replaced"
+grep -qxF "not reached" <<< "$output" && { echo "❌ Failed: exec replaces the shell"; exit 1; }
+[ "$status" -eq 4 ] && echo "✅ Passed: exec replaces the shell" || { echo "❌ Failed: exec replaces the shell"; exit 1; }
+
+# A builtin whose output can't be written says so and fails, but the shell
+# goes on, and nothing it couldn't write turns up on the restored stdout.
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo hi > /dev/full
+echo "echo status: $?"
+pwd > /dev/full
+echo "pwd status: $?"
+echo -n lost > /dev/full
+echo "after"
+EOF_INPUT
+)
+
+check "echo: write error: No space left on device"
+check "echo status: 1"
+check "pwd: write error: No space left on device"
+check "pwd status: 1"
+check "after"
+grep -qF "lost" <<< "$output" && { echo "❌ Failed: unwritten output is dropped"; exit 1; }
+echo "✅ Passed: unwritten output is dropped"
@@ -0,0 +1,37 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+cat > "$dir/args.msh" <<'EOF_SCRIPT'
+echo "name: $0"
+echo "first: $1 second: $2"
+echo "count: $#"
+echo "all: $@"
+echo "star: $*"
+echo "unset: [$3]"
+echo "braced: ${1}x"
+echo "tenth: ${10}"
+EOF_SCRIPT
+
+output=$("$shell" "$dir/args.msh" foo "bar baz" 2>&1)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "name: $dir/args.msh"
+check "first: foo second: bar baz"
+check "count: 2"
+check "all: foo bar baz"
+check "star: foo bar baz"
+check "unset: []"
+check "braced: foox"
+
+output=$("$shell" "$dir/args.msh" 1 2 3 4 5 6 7 8 9 ten 2>&1)
+check "count: 10"
+check "tenth: ten"
+
+"$shell" "$dir/missing.msh" > /dev/null 2>&1
+[ $? -eq 127 ] && echo "✅ Passed: missing script exits 127" || { echo "❌ Failed: missing script exits 127"; exit 1; }
@@ -0,0 +1,38 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+# Interrupt the shell itself while it waits for a command. Without job control
+# the child shares its process group, so only the shell sees this SIGINT, and
+# the wait has to carry on once the handler returns.
+mkfifo "$dir/input"
+"$shell" < "$dir/input" > "$dir/output" 2>&1 &
+pid=$!
+exec 3> "$dir/input"
+echo 'sleep 1; echo "waited: $?"' >&3
+sleep 0.3
+kill -INT "$pid"
+sleep 0.2
+kill -INT "$pid"
+echo 'echo "still reading"' >&3
+exec 3>&-
+wait "$pid"
+status=$?
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' "$dir/output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "waited: 0"
+check "still reading"
+[ "$status" -eq 0 ] && echo "✅ Passed: shell exits normally" || { echo "❌ Failed: shell exit status $status"; exit 1; }
+
+# The newline that moves past an interrupted job's `^C` is for the terminal,
+# so it goes to stderr, not into the output.
+output=$("$shell" -c "sh -c 'kill -INT \$\$'; echo after" 2>/dev/null)
+[ "$output" = after ] && echo "✅ Passed: interrupted job adds nothing to stdout" || { echo "❌ Failed: stdout was [$output]"; exit 1; }
@@ -0,0 +1,42 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+cat > "$dir/defs.msh" <<'EOF_SCRIPT'
+export SOURCED_VAR=from_file
+alias hi='echo hi there'
+cd /
+echo "args: $# $1"
+false
+EOF_SCRIPT
+
+output=$(cd "$dir" && "$shell" 2>&1 <<EOF_INPUT
+source defs.msh
+echo "status: \$?"
+echo "var: \$SOURCED_VAR"
+hi
+pwd
+. $dir/defs.msh one
+echo "after: \$#"
+source $dir/missing.msh
+echo "missing: \$?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "args: 0 "
+check "status: 1"
+check "var: from_file"
+check "hi there"
+check "/"
+check "args: 1 one"
+check "after: 0"
+check "source: $dir/missing.msh: No such file or directory"
+check "missing: 1"
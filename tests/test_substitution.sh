@@ -0,0 +1,76 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/sub dir"
+touch "$dir/sub dir/file.txt"
+
+output=$(cd "$dir" && FILE="$dir/sub dir/file.txt" "$shell" 2>&1 <<'EOF_INPUT'
+echo "today is $(echo 2024-01-01)"
+echo [$(printf 'one\ntwo\nthree\n')]
+echo "[$(printf 'one\ntwo\n\n\n')]"
+printf '%s\n' $(printf 'a b\nc')
+echo a$(echo " b c ")d
+echo nested $(echo $(echo deep))
+echo back `echo tick`
+echo piped $(echo hi | tr a-z A-Z)
+echo $(echo 'paren )')
+words=$(printf 'one   two'); echo "$words"
+cd "$(dirname "$FILE")"; pwd
+empty=[$(true)]; echo "$empty"
+echo $(echo first
+echo second)
+EOF_INPUT
+)
+
+output=$(sed -E 's/^((mini-shell\([^)]*\)|)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "today is 2024-01-01"
+check "[one two three]"
+check "[one"
+check "two]"
+check "a"
+check "b"
+check "c"
+check "a b c d"
+check "nested deep"
+check "back tick"
+check "piped HI"
+check "paren )"
+check "one   two"
+check "$dir/sub dir"
+check "[]"
+check "first second"
+
+# Process substitution passes a path connected to the command by a pipe.
+printf 'b\na\nc\n' > "$dir/left.txt"
+printf 'c\nb\n' > "$dir/right.txt"
+output=$(cd "$dir" && "$shell" 2>&1 <<'EOF_INPUT'
+cat <(echo hi from input)
+diff <(sort left.txt) <(sort right.txt)
+echo "diff status: $?"
+echo <(true)
+printf 'to output\n' > >(tr a-z A-Z)
+tee >(wc -l | tr -d ' ' > count.txt) < left.txt > /dev/null
+cat count.txt
+cat < <(echo redirected input)
+cat <(echo "with $(echo nested) substitution" | tr a-z A-Z)
+echo "<(quoted)"
+EOF_INPUT
+)
+
+check "hi from input"
+check "< a"
+check "diff status: 1"
+grep -qE '^/dev/fd/[0-9]+$' <<< "$output" && echo "✅ Passed: <(...) becomes a /dev/fd path" || { echo "❌ Failed: <(...) becomes a /dev/fd path"; exit 1; }
+check "TO OUTPUT"
+check "3"
+check "redirected input"
+check "WITH NESTED SUBSTITUTION"
+check "<(quoted)"
@@ -0,0 +1,39 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+echo a)
+echo "close status: $?"
+echo (a
+echo "open status: $?"
+echo $(echo b))
+echo "substitution status: $?"
+echo a |
+echo "pipe status: $?"
+echo ")" \( "$(echo "(")" $((2*(3+1)))
+EOF_INPUT
+)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "mini-shell: syntax error near unexpected token \`)'"
+check "close status: 2"
+check "mini-shell: syntax error near unexpected token \`('"
+check "open status: 2"
+check "substitution status: 2"
+check "mini-shell: syntax error near unexpected token \`|'"
+check "pipe status: 2"
+check ") ( ( 8"
+grep -qxF "a)" <<< "$output" && { echo "❌ Failed: a stray parenthesis stops the command"; exit 1; }
+echo "✅ Passed: a stray parenthesis stops the command"
+
+# Running out of input inside a quote or substitution is an error too.
+for script in 'echo "a' "echo 'a" 'echo $(echo a'; do
+    output=$(HISTFILE=/dev/null "$shell" 2>&1 <<< "$script")
+    status=$?
+    [ "$status" -eq 2 ] && echo "✅ Passed: $script exits with 2" || { echo "❌ Failed: $script exits with $status"; exit 1; }
+done
+check "mini-shell: syntax error: unexpected EOF while looking for matching \`)'"
@@ -0,0 +1,106 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/subdir"
+printf 'data\n' > "$dir/file"
+: > "$dir/empty"
+ln -s file "$dir/link"
+touch -d '2000-01-01' "$dir/old"
+
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+test -f file && echo "-f file"
+test -f subdir || echo "-f subdir: $?"
+[ -d subdir ] && echo "-d subdir"
+[ -e missing ] || echo "-e missing: $?"
+[ -s file ] && echo "-s file"
+[ -s empty ] || echo "-s empty"
+[ -L link ] && echo "-L link"
+[ -h file ] || echo "-h file"
+[ -r file -a -w file ] && echo "-r -a -w"
+[ -x subdir ] && echo "-x subdir"
+[ file -nt old ] && echo "-nt"
+[ old -ot file ] && echo "-ot"
+[ link -ef file ] && echo "-ef"
+x=""
+test -z "$x" && echo "-z empty"
+test -n "$x" || echo "-n empty"
+test "$x" || echo "empty string"
+test word && echo "one word"
+test && echo never || echo "no arguments: $?"
+a=same b=same
+test "$a" = "$b" && echo "strings equal"
+test "$a" != "$b" || echo "not unequal"
+[ apple "<" banana ] && echo "less than"
+test 3 -lt 5 && echo "3 -lt 5"
+test 10 -gt 9 && echo "10 -gt 9"
+test -2 -le -2 && echo "-2 -le -2"
+[ 7 -eq 07 ] && echo "7 -eq 07"
+[ 1 -ne 1 ] || echo "1 -ne 1: $?"
+[ ! -f missing ] && echo "! -f missing"
+[ ! "" ] && echo "! empty"
+[ -n x -a -z "" ] && echo "-a"
+[ -z x -o -n x ] && echo "-o"
+[ \( -z x -o -n x \) -a ! -z x ] && echo "parentheses"
+[ = = = ] && echo "= = ="
+[ -f file
+echo "missing bracket: $?"
+test 1 -lt abc
+echo "not an integer: $?"
+test a b
+echo "unary expected: $?"
+test a b c d e
+echo "too many: $?"
+help test
+EOF_INPUT
+)
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "-f file"
+check "-f subdir: 1"
+check "-d subdir"
+check "-e missing: 1"
+check "-s file"
+check "-s empty"
+check "-L link"
+check "-h file"
+check "-r -a -w"
+check "-x subdir"
+check "-nt"
+check "-ot"
+check "-ef"
+check "-z empty"
+check "-n empty"
+check "empty string"
+check "one word"
+check "no arguments: 1"
+check "strings equal"
+check "not unequal"
+check "less than"
+check "3 -lt 5"
+check "10 -gt 9"
+check "-2 -le -2"
+check "7 -eq 07"
+check "1 -ne 1: 1"
+check "! -f missing"
+check "! empty"
+check "-a"
+check "-o"
+check "parentheses"
+check "= = ="
+check "[: missing \`]'"
+check "missing bracket: 2"
+check "test: abc: integer expression expected"
+check "not an integer: 2"
+check "test: a: unary operator expected"
+check "unary expected: 2"
+check "test: too many arguments"
+check "too many: 2"
+check "test: test [expression]"
+grep -qxF "never" <<< "$output" && { echo "❌ Failed: no arguments is false"; exit 1; }
+echo "✅ Passed: no arguments is false"
@@ -0,0 +1,35 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+start=$(date +%s)
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+timeout 1 sleep 10
+echo "timed out: $?"
+timeout 5 sh -c 'exit 3'
+echo "finished: $?"
+timeout 1 sh -c 'trap "" TERM; sleep 10'
+echo "ignored term: $?"
+timeout soon sleep 1
+echo "invalid: $?"
+timeout 5
+echo "no command: $?"
+EOF_INPUT
+)
+elapsed=$(( $(date +%s) - start ))
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "timed out: 124"
+check "finished: 3"
+check "ignored term: 124"
+check "timeout: invalid time interval 'soon'"
+check "invalid: 125"
+check "timeout: usage: timeout secs command ..."
+check "no command: 125"
+# Two one-second timeouts plus the grace period before SIGKILL.
+[ "$elapsed" -lt 8 ] && echo "✅ Passed: timeout stops the command in time" || { echo "❌ Failed: took ${elapsed}s"; exit 1; }
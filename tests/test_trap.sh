@@ -0,0 +1,57 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+trap 'echo leaving' EXIT
+trap 'echo caught usr1' USR1
+trap '' TERM
+trap
+sh -c 'kill -USR1 $PPID'
+echo "after usr1"
+sh -c 'kill -TERM $PPID'
+echo "survived term"
+trap 'echo caught int' INT
+sh -c 'kill -INT $PPID'
+trap - USR1 INT
+trap
+trap 'echo nope' BOGUS
+echo "bad signal: $?"
+trap -
+echo "no signal: $?"
+false
+exit 4
+EOF_INPUT
+)
+status=$?
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "trap -- 'echo leaving' EXIT"
+check "trap -- 'echo caught usr1' SIGUSR1"
+check "trap -- '' SIGTERM"
+check "caught usr1"
+check "after usr1"
+check "survived term"
+check "caught int"
+[ "$(grep -c "SIGUSR1" <<< "$output")" -eq 1 ] && echo "✅ Passed: trap - resets the signal" || { echo "❌ Failed: trap - resets the signal"; exit 1; }
+check "trap: BOGUS: invalid signal specification"
+check "bad signal: 1"
+check "trap: usage: trap [command] signal ..."
+check "no signal: 2"
+[ "$(tail -1 <<< "$output")" = "leaving" ] && echo "✅ Passed: the EXIT trap runs last" || { echo "❌ Failed: the EXIT trap runs last"; exit 1; }
+[ "$status" -eq 4 ] && echo "✅ Passed: the EXIT trap keeps the exit status" || { echo "❌ Failed: exited with $status"; exit 1; }
+
+# An ignored signal stays ignored in commands the shell runs, and the EXIT
+# trap runs when input runs out too.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+trap 'echo end of input' EXIT
+trap '' USR2
+sh -c 'kill -USR2 $$; echo child ignored usr2'
+exit 3 | cat
+EOF_INPUT
+)
+check "child ignored usr2"
+[ "$(grep -c "end of input" <<< "$output")" -eq 1 ] && echo "✅ Passed: exit in a pipeline doesn't run the EXIT trap" || { echo "❌ Failed: exit in a pipeline doesn't run the EXIT trap"; exit 1; }
@@ -0,0 +1,50 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+mkdir "$dir/bin" "$dir/bin/subdir"
+printf '#!/bin/sh\n' > "$dir/bin/tool"
+chmod +x "$dir/bin/tool"
+printf 'data\n' > "$dir/bin/data"
+
+output=$(cd "$dir" && PATH="$dir/bin:$PATH" "$shell" 2>&1 <<'EOF_INPUT'
+type cd
+type tool
+alias ll='ls -la'
+type ll
+type data
+echo "data status: $?"
+type subdir
+type cd nonexistent_command
+echo "status: $?"
+which tool
+which nonexistent_command tool
+echo "which status: $?"
+which bin/tool
+cd bin; export PATH=":/usr/bin"; which tool
+which ./data
+echo "which data: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "cd is a shell builtin"
+check "tool is $dir/bin/tool"
+check "ll is aliased to \`ls -la'"
+check "type: data: not found"
+check "data status: 1"
+check "type: subdir: not found"
+check "type: nonexistent_command: not found"
+check "status: 1"
+check "$dir/bin/tool"
+check "which status: 1"
+check "bin/tool"
+check "./tool"
+check "which data: 1"
@@ -0,0 +1,36 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+output=$(cd "$dir" && HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+umask 022
+umask
+printf x > default.txt
+umask 077
+umask
+printf x > private.txt
+umask 9
+echo "invalid status: $?"
+EOF_INPUT
+)
+
+output=$(sed -E 's/^(mini-shell\([^)]*\)> )+//' <<< "$output")
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "0022"
+check "0077"
+check "umask: 9: octal number out of range"
+check "invalid status: 1"
+
+mode() {
+    [ "$(stat -c %a "$dir/$1")" = "$2" ] && echo "✅ Passed: $1 is $2" || { echo "❌ Failed: $1 is $(stat -c %a "$dir/$1"), not $2"; exit 1; }
+}
+
+mode default.txt 644
+mode private.txt 600
@@ -0,0 +1,52 @@
+#!/bin/bash
+shell="$(pwd)/target/debug/mini-shell"
+cargo build -q || exit 1
+
+start=$(date +%s%N)
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sleep 0.5 &
+sh -c 'sleep 0.2; exit 3' &
+wait %2
+echo "job status: $?"
+wait
+echo "all status: $?"
+jobs
+sh -c 'exit 5' | sh -c 'sleep 0.1; exit 6' &
+wait %1
+echo "pipeline status: $?"
+wait %7
+echo "unknown job: $?"
+wait 999999
+echo "unknown pid: $?"
+wait nope
+echo "bad spec: $?"
+EOF_INPUT
+)
+elapsed=$(( ($(date +%s%N) - start) / 1000000 ))
+
+check() {
+    grep -qxF -- "$1" <<< "$output" && echo "✅ Passed: $1" || { echo "❌ Failed: $1"; exit 1; }
+}
+
+check "job status: 3"
+check "all status: 0"
+grep -q "Running" <<< "$output" && { echo "❌ Failed: wait leaves no jobs behind"; exit 1; }
+echo "✅ Passed: wait leaves no jobs behind"
+[ "$elapsed" -ge 500 ] && echo "✅ Passed: wait blocks until the jobs finish" || { echo "❌ Failed: wait returned after ${elapsed}ms"; exit 1; }
+check "pipeline status: 6"
+check "wait: %7: no such job"
+check "unknown job: 127"
+check "wait: pid 999999 is not a child of this shell"
+check "unknown pid: 127"
+check "wait: nope: not a pid or valid job spec"
+check "bad spec: 2"
+
+# `wait $!` waits for the last background command.
+output=$(HISTFILE=/dev/null "$shell" 2>&1 <<'EOF_INPUT'
+sh -c 'sleep 0.2; exit 5' &
+wait $!
+echo "last background: $?"
+EOF_INPUT
+)
+
+check "last background: 5"